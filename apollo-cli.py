@@ -10,6 +10,7 @@ from http.cookies import SimpleCookie
 
 API_BASE = os.environ.get("APOLLO_SERVER", "http://127.0.0.1:8080").rstrip("/") + "/api"
 DEFAULT_PUZZLE_VALUE = 32
+NORMALIZATIONS = ["trim", "case_fold", "nfc", "collapse_whitespace", "strip_accents"]
 PROG = "apollo-cli.py"
 
 
@@ -58,7 +59,7 @@ def print_response_text(text):
         print(json.dumps(data, ensure_ascii=False, indent=2))
 
 
-def request_json(method, path, payload=None, sid=None):
+def request_json(method, path, payload=None, sid=None, admin_sid=None):
     body = None
     headers = {}
     if payload is not None:
@@ -66,6 +67,8 @@ def request_json(method, path, payload=None, sid=None):
         headers["Content-Type"] = "application/json"
     if sid:
         headers["Cookie"] = f"sid={sid}"
+    if admin_sid:
+        headers["Cookie"] = f"admin_sid={admin_sid}"
 
     req = urllib.request.Request(
         API_BASE + path, data=body, headers=headers, method=method
@@ -84,13 +87,25 @@ def request_json(method, path, payload=None, sid=None):
         cli_error(f"request failed: {err.reason}")
 
 
-def extract_sid(set_cookie_headers):
+def extract_sid(set_cookie_headers, name="sid"):
     for header in set_cookie_headers:
         cookie = SimpleCookie()
         cookie.load(header)
-        if "sid" in cookie:
-            return cookie["sid"].value
-    cli_error("missing sid cookie in response")
+        if name in cookie:
+            return cookie[name].value
+    cli_error(f"missing {name} cookie in response")
+
+
+ADMIN_SIDS = {}
+
+
+def admin_login(password):
+    if password not in ADMIN_SIDS:
+        _, headers, _ = request_json("POST", "/admin/login", {"password": password})
+        ADMIN_SIDS[password] = extract_sid(
+            headers.get_all("Set-Cookie", []), "admin_sid"
+        )
+    return ADMIN_SIDS[password]
 
 
 def cmd_set_admin_password(args):
@@ -102,22 +117,173 @@ def cmd_set_admin_password(args):
     print_response_text(text)
 
 
+def answer_checker(args):
+    """`--numeric`, `--regex`, `--external` or `--per-team` take the solution as the number, the pattern, the program or the template"""
+    kinds = ["numeric", "regex", "external", "per_team"]
+    numeric, regex, external, per_team = (getattr(args, kind, False) for kind in kinds)
+    if (numeric or regex or external) and args.solution is None:
+        cli_error("--numeric, --regex and --external need a solution")
+    if per_team:
+        return {"PerTeam": {"secret": "", "template": args.solution or "{hmac}"}}
+    if numeric:
+        try:
+            number = float(args.solution.replace(",", "."))
+        except ValueError:
+            cli_error(f"not a number: {args.solution!r}")
+        return {
+            "NumericRange": {
+                "min": number - args.tolerance,
+                "max": number + args.tolerance,
+            }
+        }
+    if regex:
+        return {"Regex": {"pattern": args.solution}}
+    if external:
+        return {"External": {"program": args.solution}}
+    return None
+
+
+def puzzle_meta(args):
+    """what contestants see of the puzzle, `None` if none of it is given"""
+    fields = ["title", "statement", "tag", "difficulty", "order"]
+    given = {field: getattr(args, field, None) for field in fields}
+    if all(arg is None for arg in given.values()):
+        return None
+    return {
+        "title": given["title"] or "",
+        "statement": given["statement"] or "",
+        "tags": given["tag"] or [],
+        "difficulty": given["difficulty"],
+        "order": given["order"] or 0,
+    }
+
+
+def add_meta_arguments(p):
+    p.add_argument("--title")
+    p.add_argument("--statement", help="markdown")
+    p.add_argument("--tag", action="append", help="category or tag")
+    p.add_argument("--difficulty", choices=["Easy", "Medium", "Hard"])
+    p.add_argument("--order", type=int, help="puzzles are listed by this, ascending")
+
+
+def add_checker_arguments(p):
+    checker = p.add_mutually_exclusive_group()
+    checker.add_argument("--numeric", action="store_true", help="solution is a number")
+    checker.add_argument("--regex", action="store_true", help="solution is a pattern")
+    checker.add_argument(
+        "--external",
+        action="store_true",
+        help="solution is the absolute path of a checker program on the server",
+    )
+    checker.add_argument(
+        "--per-team",
+        action="store_true",
+        help="solution is a template like flag{hmac}, see team_answers",
+    )
+    p.add_argument("--tolerance", type=float, default=0.0, help="± for --numeric")
+
+
 def cmd_create_puzzle(args):
+    puzzle = {
+        "solution": args.solution,
+        "value": getattr(args, "value", DEFAULT_PUZZLE_VALUE),
+    }
+    if getattr(args, "alternative", None):
+        puzzle["alternatives"] = args.alternative
+    meta = puzzle_meta(args)
+    if meta is not None:
+        puzzle["meta"] = meta
+    checker = answer_checker(args)
+    if checker is not None:
+        puzzle["checker"] = checker
+        puzzle["solution"] = ""
+    if getattr(args, "normalize", None):
+        puzzle["normalization"] = {option: True for option in args.normalize}
+    if getattr(args, "bonuses", None):
+        puzzle["bonuses"] = [int(bonus) for bonus in args.bonuses.split(",")]
+    if getattr(args, "penalty", None) is not None:
+        puzzle["attempt_rules"] = {
+            "penalty": args.penalty,
+            "cooldown_after": args.cooldown_after,
+            "cooldown_secs": args.cooldown_secs,
+            "max_attempts": args.max_attempts,
+        }
+    if getattr(args, "curve", None):
+        puzzle["decay"] = {
+            "minimum": args.minimum,
+            "solves": args.decay_solves,
+            "curve": args.curve,
+        }
+    payload = {"puzzle_solutions": {args.id: puzzle}}
+    _, _, text = request_json(
+        "POST", "/set_solution", payload, admin_sid=admin_login(args.password)
+    )
+    print_response_text(text)
+
+
+def cmd_edit_puzzle(args):
     payload = {
-        "puzzle_solutions": {
-            args.id: {
-                "solution": args.solution,
-                "value": DEFAULT_PUZZLE_VALUE,
-            }
-        },
-        "password": args.password,
+        "puzzle_id": args.id,
+        "value": args.value,
+        "solution": args.solution,
+        "alternatives": args.alternative,
+        "checker": answer_checker(args),
+        "meta": puzzle_meta(args),
+        "rotate_secret": args.rotate_secret,
+    }
+    if payload["checker"] is not None:
+        payload["solution"] = None
+    _, _, text = request_json(
+        "POST", "/admin/edit_puzzle", payload, admin_sid=admin_login(args.password)
+    )
+    print_response_text(text)
+
+
+def cmd_delete_puzzle(args):
+    payload = {
+        "puzzle_id": args.id,
+        "policy": "KeepHistory" if args.keep_history else "Purge",
     }
-    _, _, text = request_json("POST", "/set_solution", payload)
+    _, _, text = request_json(
+        "POST", "/admin/delete_puzzle", payload, admin_sid=admin_login(args.password)
+    )
+    print_response_text(text)
+
+
+def cmd_submissions(args):
+    _, _, text = request_json(
+        "GET", "/submissions", admin_sid=admin_login(args.password)
+    )
+    print_response_text(text)
+
+
+def cmd_team_answers(args):
+    _, _, text = request_json(
+        "POST",
+        "/admin/team_answers",
+        {"teams": args.team or []},
+        admin_sid=admin_login(args.password),
+    )
+    print_response_text(text)
+
+
+def cmd_team_password(args):
+    payload = {"team": args.team, "password": args.team_password}
+    _, _, text = request_json(
+        "POST", "/admin/team_password", payload, admin_sid=admin_login(args.password)
+    )
+    print_response_text(text)
+
+
+def cmd_standings(args):
+    _, _, text = request_json("GET", "/standings")
     print_response_text(text)
 
 
 def cmd_join(args):
-    _, headers, text = request_json("POST", "/join", {"username": args.username})
+    _, headers, text = request_json(
+        "POST", "/join", {"username": args.username, "password": args.password}
+    )
     sid = extract_sid(headers.get_all("Set-Cookie", []))
     save_user_cookie(args.username, sid)
     print_response_text(text)
@@ -129,6 +295,38 @@ def cmd_auth_state(args):
     print_response_text(text)
 
 
+def cmd_team_state(args):
+    sid = load_user_cookie(args.username)
+    _, _, text = request_json("GET", "/team_state", sid=sid)
+    print_response_text(text)
+
+
+def cmd_puzzles(args):
+    sid = load_user_cookie(args.username)
+    _, _, text = request_json("GET", "/puzzles", sid=sid)
+    print_response_text(text)
+
+
+def cmd_sessions(args):
+    sid = load_user_cookie(args.username)
+    _, _, text = request_json("GET", "/sessions", sid=sid)
+    print_response_text(text)
+
+
+def cmd_revoke_session(args):
+    sid = load_user_cookie(args.username)
+    payload = {"session_id": args.session_id}
+    _, _, text = request_json("POST", "/revoke_session", payload, sid=sid)
+    print_response_text(text)
+
+
+def cmd_set_team_password(args):
+    sid = load_user_cookie(args.username)
+    payload = {"password": args.password}
+    _, _, text = request_json("POST", "/team_password", payload, sid=sid)
+    print_response_text(text)
+
+
 def cmd_submit(args):
     sid = load_user_cookie(args.username)
     payload = {"puzzle_id": args.id, "solution": args.solution}
@@ -158,7 +356,9 @@ def cmd_mock_join(args):
     start_idx = string.ascii_lowercase.index(args.from_char)
     end_idx = string.ascii_lowercase.index(args.to_char)
     for username in string.ascii_lowercase[start_idx : end_idx + 1]:
-        cmd_join(argparse.Namespace(username=f"user-{username}"))
+        cmd_join(
+            argparse.Namespace(username=f"user-{username}", password=f"pwd-{username}")
+        )
 
 
 def lower_char(value):
@@ -180,16 +380,106 @@ def build_parser():
     p.add_argument("id")
     p.add_argument("solution")
     p.add_argument("password")
+    p.add_argument("--value", type=int, default=DEFAULT_PUZZLE_VALUE)
+    p.add_argument("--curve", choices=["Linear", "Quadratic"])
+    p.add_argument("--minimum", type=int, default=1)
+    p.add_argument("--decay-solves", type=int, default=10)
+    p.add_argument("--alternative", action="append", help="other accepted solution")
+    p.add_argument("--bonuses", help="comma separated, e.g. 10,5,2")
+    p.add_argument(
+        "--normalize",
+        action="append",
+        choices=NORMALIZATIONS,
+        help="applied to the solutions and the answers too",
+    )
+    add_checker_arguments(p)
+    add_meta_arguments(p)
+    p.add_argument("--penalty", type=int, help="own rules for wrong attempts")
+    p.add_argument("--cooldown-after", type=int, default=0)
+    p.add_argument("--cooldown-secs", type=int, default=60)
+    p.add_argument("--max-attempts", type=int, default=0)
     p.set_defaults(func=cmd_create_puzzle)
 
+    p = subparsers.add_parser(
+        "edit_puzzle",
+        description="--title, --statement, --tag, --difficulty and --order replace all the metadata",
+    )
+    p.add_argument("id")
+    p.add_argument("password")
+    p.add_argument("--value", type=int)
+    p.add_argument("--solution")
+    p.add_argument("--alternative", action="append", help="replaces all of them")
+    add_checker_arguments(p)
+    p.add_argument(
+        "--rotate-secret",
+        action="store_true",
+        help="new answers for --per-team, the secret is kept otherwise",
+    )
+    add_meta_arguments(p)
+    p.set_defaults(func=cmd_edit_puzzle)
+
+    p = subparsers.add_parser("delete_puzzle")
+    p.add_argument("id")
+    p.add_argument("password")
+    p.add_argument(
+        "--keep-history", action="store_true", help="the id can't be used again then"
+    )
+    p.set_defaults(func=cmd_delete_puzzle)
+
+    p = subparsers.add_parser("submissions")
+    p.add_argument("password")
+    p.set_defaults(func=cmd_submissions)
+
+    p = subparsers.add_parser("team_answers")
+    p.add_argument("password")
+    p.add_argument("--team", action="append", help="one that hasn't joined yet")
+    p.set_defaults(func=cmd_team_answers)
+
+    p = subparsers.add_parser(
+        "team_password", description="set the password of a team as admin"
+    )
+    p.add_argument("team")
+    p.add_argument("team_password")
+    p.add_argument("password")
+    p.set_defaults(func=cmd_team_password)
+
+    p = subparsers.add_parser("standings")
+    p.set_defaults(func=cmd_standings)
+
     p = subparsers.add_parser("join")
     p.add_argument("username")
+    p.add_argument("password")
     p.set_defaults(func=cmd_join)
 
     p = subparsers.add_parser("auth_state")
     p.add_argument("username")
     p.set_defaults(func=cmd_auth_state)
 
+    p = subparsers.add_parser("team_state")
+    p.add_argument("username")
+    p.set_defaults(func=cmd_team_state)
+
+    p = subparsers.add_parser("puzzles")
+    p.add_argument("username")
+    p.set_defaults(func=cmd_puzzles)
+
+    p = subparsers.add_parser("sessions")
+    p.add_argument("username")
+    p.set_defaults(func=cmd_sessions)
+
+    p = subparsers.add_parser("revoke_session")
+    p.add_argument("username")
+    p.add_argument("session_id")
+    p.set_defaults(func=cmd_revoke_session)
+
+    p = subparsers.add_parser(
+        "set_team_password",
+        description="set the password of your team, if it has none yet",
+    )
+    p.add_argument("username")
+    p.add_argument("password")
+    p.set_defaults(func=cmd_set_team_password)
+
     p = subparsers.add_parser("submit")
     p.add_argument("username")
     p.add_argument("id")
@@ -1,26 +1,100 @@
+use crate::backend::models::{Difficulty, Timestamp};
 use dioxus::prelude::*;
+use std::time::Duration;
+
+mod admin;
+mod join;
+mod layout;
+mod puzzles;
+mod scoreboard;
+
+use admin::Admin;
+use join::Join;
+use layout::{Layout, NotFound};
+use puzzles::Puzzles;
+use scoreboard::Scoreboard;
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const MAIN_CSS: Asset = asset!("/assets/main.css");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 
+/// who's logged in, shared by [`Layout`] with every page, restart it after joining or leaving
+type AuthState = Resource<Result<String, HttpError>>;
+
+#[derive(Routable, Clone, PartialEq)]
+#[rustfmt::skip]
+enum Route {
+    #[layout(Layout)]
+        #[route("/")]
+        Join {},
+        #[route("/scoreboard")]
+        Scoreboard {},
+        #[route("/puzzles")]
+        Puzzles {},
+        #[route("/admin")]
+        Admin {},
+        #[route("/:..segments")]
+        NotFound { segments: Vec<String> },
+}
+
 #[component]
 pub fn App() -> Element {
     rsx! {
         head {
             document::Link { rel: "icon", href: FAVICON }
-            document::Title { "Apollo" }
-            // document::Link { rel: "stylesheet", href: MAIN_CSS }
-            // document::Link { rel: "stylesheet", href: TAILWIND_CSS }
+            document::Link { rel: "stylesheet", href: MAIN_CSS }
+            document::Link { rel: "stylesheet", href: TAILWIND_CSS }
         }
 
-        body {
-            h1 { "Apollo" }
-            "our client is "
-            a { href: "https://github.com/csboo/apollo/pull/4", "work-in-progress" }
-            br {}
-            "meanwhile, make sure to check out our "
-            a { href: "https://github.com/csboo/apollo", "github page" }
-        }
+        body { class: "min-h-screen bg-slate-50 text-slate-900", Router::<Route> {} }
+    }
+}
+
+/// the human-readable part of an error returned by our endpoints, without the status code
+fn error_msg(err: &HttpError) -> String {
+    err.message
+        .clone()
+        .unwrap_or_else(|| err.status.to_string())
+}
+
+/// wait `dur` without blocking, on whichever runtime we're on
+async fn sleep(dur: Duration) {
+    #[cfg(feature = "server")]
+    tokio::time::sleep(dur).await;
+    #[cfg(all(feature = "web", not(feature = "server")))]
+    gloo_timers::future::sleep(dur).await;
+    #[cfg(not(any(feature = "web", feature = "server")))]
+    {
+        _ = dur;
+        std::future::pending::<()>().await; // no timer to wait on, never wake up
+    }
+}
+
+/// wall-clock time of `ts`, local in the browser, UTC elsewhere
+fn format_time(ts: Timestamp) -> String {
+    #[cfg(all(feature = "web", not(feature = "server")))]
+    {
+        js_sys::Date::new(&(ts as f64).into())
+            .to_locale_time_string("hu-HU")
+            .into()
+    }
+    #[cfg(not(all(feature = "web", not(feature = "server"))))]
+    {
+        let secs = ts / 1000 % (24 * 60 * 60);
+        format!(
+            "{:02}:{:02}:{:02} UTC",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+/// how [`Difficulty`] is shown
+fn difficulty_label(difficulty: Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Easy => "könnyű",
+        Difficulty::Medium => "közepes",
+        Difficulty::Hard => "nehéz",
     }
 }
@@ -0,0 +1,209 @@
+use super::{error_msg, format_time};
+use crate::backend::endpoints::{
+    admin_auth_state, admin_login, admin_logout, admin_pwd_set, set_passwd,
+};
+use dioxus::prelude::*;
+
+mod existing;
+mod puzzles;
+mod submissions;
+mod team_answers;
+mod team_password;
+
+use existing::ExistingPuzzles;
+use puzzles::PuzzleEditor;
+use submissions::SubmissionHistory;
+use team_answers::TeamAnswersExport;
+use team_password::TeamPassword;
+
+/// host's page: first-run password setup, then logging in for puzzle authoring and submission history
+#[component]
+pub fn Admin() -> Element {
+    let mut pwd_set = use_resource(admin_pwd_set);
+
+    rsx! {
+        main { class: "mx-auto max-w-4xl p-6 flex flex-col gap-4",
+            h1 { class: "text-3xl font-bold", "adminisztráció" }
+            match pwd_set() {
+                None => rsx! {
+                    p { class: "text-slate-500", "betöltés..." }
+                },
+                Some(Err(e)) => rsx! {
+                    p { class: "text-red-600", "{e}" }
+                },
+                Some(Ok(true)) => rsx! {
+                    p { class: "text-green-700", "a mesterjelszó már be van állítva" }
+                    AdminSession {}
+                },
+                Some(Ok(false)) => rsx! {
+                    PasswordSetup { on_done: move |_| pwd_set.restart() }
+                },
+            }
+        }
+    }
+}
+
+/// set the master password with the initial one from the server log
+#[component]
+fn PasswordSetup(on_done: EventHandler<()>) -> Element {
+    let mut init_password = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut password_again = use_signal(String::new);
+    let mut pending = use_signal(|| false);
+    let mut outcome = use_signal(|| None::<Result<String, String>>);
+
+    let on_submit = move |evt: FormEvent| async move {
+        evt.prevent_default();
+        if init_password().is_empty() || password().is_empty() {
+            outcome.set(Some(Err(String::from("minden mezőt ki kell tölteni"))));
+            return;
+        }
+        if password() != password_again() {
+            outcome.set(Some(Err(String::from("a két jelszó nem egyezik"))));
+            return;
+        }
+
+        pending.set(true);
+        outcome.set(None);
+        match set_passwd(init_password(), password()).await {
+            Ok(msg) => {
+                init_password.write().clear();
+                password.write().clear();
+                password_again.write().clear();
+                outcome.set(Some(Ok(msg)));
+                on_done.call(());
+            }
+            Err(e) => outcome.set(Some(Err(error_msg(&e)))),
+        }
+        pending.set(false);
+    };
+
+    rsx! {
+        p {
+            "a beállítási jelszót a szerver indításkor kiírja a naplójába ("
+            code { "initial admin password" }
+            ")"
+        }
+        form { class: "flex flex-col gap-2", onsubmit: on_submit,
+            label { r#for: "init-password", "beállítási jelszó" }
+            input {
+                id: "init-password",
+                class: "rounded border px-3 py-1 font-mono",
+                autocomplete: "off",
+                value: "{init_password}",
+                oninput: move |e| init_password.set(e.value()),
+            }
+            label { r#for: "password", "új mesterjelszó" }
+            input {
+                id: "password",
+                class: "rounded border px-3 py-1",
+                r#type: "password",
+                autocomplete: "new-password",
+                value: "{password}",
+                oninput: move |e| password.set(e.value()),
+            }
+            label { r#for: "password-again", "új mesterjelszó még egyszer" }
+            input {
+                id: "password-again",
+                class: "rounded border px-3 py-1",
+                r#type: "password",
+                autocomplete: "new-password",
+                value: "{password_again}",
+                oninput: move |e| password_again.set(e.value()),
+            }
+            button {
+                class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50",
+                r#type: "submit",
+                disabled: pending(),
+                "beállítás"
+            }
+        }
+
+        if pending() {
+            p { class: "text-slate-500",
+                "a jelszó hasítása és az elmentett állapot betöltése folyamatban, ez eltarthat egy darabig..."
+            }
+        }
+        match outcome() {
+            Some(Ok(msg)) => rsx! {
+                p { class: "text-green-700", "{msg}" }
+            },
+            Some(Err(msg)) => rsx! {
+                p { class: "text-red-600", "{msg}" }
+            },
+            None => rsx! {},
+        }
+    }
+}
+
+/// log in with the master password once, then manage the game until the session expires
+#[component]
+fn AdminSession() -> Element {
+    let mut auth = use_resource(admin_auth_state);
+    let mut password = use_signal(String::new);
+    let mut pending = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    // bumped when puzzles are added, so the existing ones are reloaded
+    let mut puzzles_added = use_signal(|| 0);
+
+    let on_login = move |evt: FormEvent| async move {
+        evt.prevent_default();
+        pending.set(true);
+        // the password isn't kept around after logging in
+        let password = password.replace(String::new());
+        match admin_login(password).await {
+            Ok(_) => {
+                error.set(None);
+                auth.restart();
+            }
+            Err(e) => error.set(Some(error_msg(&e))),
+        }
+        pending.set(false);
+    };
+    let on_logout = move |_| async move {
+        match admin_logout().await {
+            Ok(_) => auth.restart(),
+            Err(e) => error.set(Some(error_msg(&e))),
+        }
+    };
+
+    rsx! {
+        match auth() {
+            None => rsx! {
+                p { class: "text-slate-500", "betöltés..." }
+            },
+            Some(Ok(expires_at)) => rsx! {
+                div { class: "flex items-center justify-between",
+                    p { "adminként vagy bejelentkezve, a munkamenet lejár: {format_time(expires_at)}" }
+                    button { class: "rounded border px-3 py-1", onclick: on_logout, "kijelentkezés" }
+                }
+                PuzzleEditor { on_saved: move |_| puzzles_added += 1 }
+                ExistingPuzzles { refresh: puzzles_added }
+                TeamAnswersExport {}
+                TeamPassword {}
+                SubmissionHistory {}
+            },
+            Some(Err(_)) => rsx! {
+                form { class: "flex gap-2", onsubmit: on_login,
+                    input {
+                        class: "flex-1 rounded border px-3 py-1",
+                        r#type: "password",
+                        placeholder: "mesterjelszó",
+                        autocomplete: "current-password",
+                        value: "{password}",
+                        oninput: move |e| password.set(e.value()),
+                    }
+                    button {
+                        class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50",
+                        r#type: "submit",
+                        disabled: pending(),
+                        "bejelentkezés"
+                    }
+                }
+            },
+        }
+        if let Some(error) = error() {
+            p { class: "text-red-600", "{error}" }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::app::error_msg;
+use crate::backend::endpoints::{admin_puzzles, delete_puzzle, edit_puzzle};
+use crate::backend::models::*;
+use dioxus::prelude::*;
+
+/// fix or delete puzzles that are already set
+///
+/// reloads whenever `refresh` changes
+#[component]
+pub fn ExistingPuzzles(refresh: ReadSignal<u32>) -> Element {
+    let mut state = use_resource(move || {
+        _ = refresh();
+        admin_puzzles()
+    });
+
+    let mut puzzles = match state() {
+        None => {
+            return rsx! {
+                p { class: "text-slate-500", "betöltés..." }
+            };
+        }
+        Some(Err(e)) => {
+            return rsx! {
+                p { class: "text-red-600", "{error_msg(&e)}" }
+            };
+        }
+        Some(Ok(existing)) => existing.into_iter().collect::<Vec<_>>(),
+    };
+    puzzles.sort();
+
+    rsx! {
+        h2 { class: "text-xl font-bold", "meglévő feladatok" }
+        if puzzles.is_empty() {
+            p { class: "text-slate-500", "még nincsenek feladatok" }
+        }
+        for (id , value) in puzzles {
+            ExistingPuzzle {
+                key: "{id}",
+                id,
+                value,
+                on_change: move |_| state.restart(),
+            }
+        }
+    }
+}
+
+/// a single puzzle to change the value or the solution of, or to delete
+#[component]
+fn ExistingPuzzle(id: PuzzleId, value: PuzzleValue, on_change: EventHandler<()>) -> Element {
+    let mut new_value = use_signal(|| value.to_string());
+    let mut new_solution = use_signal(String::new);
+    let mut replace_alternatives = use_signal(|| false);
+    let mut rotate_secret = use_signal(|| false);
+    // one per line
+    let mut new_alternatives = use_signal(String::new);
+    let mut keep_history = use_signal(|| false);
+    let mut pending = use_signal(|| false);
+    let mut outcome = use_signal(|| None::<Result<String, String>>);
+
+    let puzzle_id = id.clone();
+    let on_edit = move |evt: FormEvent| {
+        let id = puzzle_id.clone();
+        async move {
+            evt.prevent_default();
+            let Ok(new_value) = new_value().trim().parse::<PuzzleValue>() else {
+                outcome.set(Some(Err(format!(
+                    "érvénytelen pontérték: {:?}",
+                    new_value()
+                ))));
+                return;
+            };
+            let value = (new_value != value).then_some(new_value);
+            let solution = Some(new_solution()).filter(|solution| !solution.trim().is_empty());
+            let alternatives = replace_alternatives().then(|| {
+                (new_alternatives.read().lines())
+                    .filter(|alternative| !alternative.trim().is_empty())
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            });
+            if value.is_none() && solution.is_none() && alternatives.is_none() && !rotate_secret() {
+                outcome.set(Some(Err(String::from("nincs mit módosítani"))));
+                return;
+            }
+
+            pending.set(true);
+            let rotate = Some(rotate_secret());
+            match edit_puzzle(id, value, solution, alternatives, None, None, rotate).await {
+                Ok(msg) => {
+                    new_solution.write().clear();
+                    new_alternatives.write().clear();
+                    replace_alternatives.set(false);
+                    rotate_secret.set(false);
+                    outcome.set(Some(Ok(msg)));
+                    on_change.call(());
+                }
+                Err(e) => outcome.set(Some(Err(error_msg(&e)))),
+            }
+            pending.set(false);
+        }
+    };
+
+    let puzzle_id = id.clone();
+    let on_delete = move |_| {
+        let id = puzzle_id.clone();
+        async move {
+            let policy = if keep_history() {
+                DeletePolicy::KeepHistory
+            } else {
+                DeletePolicy::Purge
+            };
+            pending.set(true);
+            match delete_puzzle(id, policy).await {
+                Ok(_) => on_change.call(()),
+                Err(e) => outcome.set(Some(Err(error_msg(&e)))),
+            }
+            pending.set(false);
+        }
+    };
+
+    rsx! {
+        section { class: "rounded border bg-white p-3 flex flex-col gap-2",
+            form { class: "flex flex-wrap items-center gap-2", onsubmit: on_edit,
+                span { class: "font-mono font-bold w-24", "{id}" }
+                input {
+                    class: "w-24 rounded border px-2 py-1",
+                    r#type: "number",
+                    min: "0",
+                    title: "pontérték",
+                    value: "{new_value}",
+                    oninput: move |e| new_value.set(e.value()),
+                }
+                input {
+                    class: "flex-1 rounded border px-2 py-1",
+                    autocomplete: "off",
+                    placeholder: "új megoldás (üresen marad a régi)",
+                    value: "{new_solution}",
+                    oninput: move |e| new_solution.set(e.value()),
+                }
+                button {
+                    class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50",
+                    r#type: "submit",
+                    disabled: pending(),
+                    "módosítás"
+                }
+            }
+            label { class: "flex items-center gap-1 text-sm",
+                input {
+                    r#type: "checkbox",
+                    checked: replace_alternatives(),
+                    onchange: move |e| replace_alternatives.set(e.checked()),
+                }
+                "további elfogadott megoldások cseréje"
+            }
+            if replace_alternatives() {
+                textarea {
+                    class: "rounded border px-2 py-1 text-sm",
+                    rows: "2",
+                    placeholder: "soronként egy, üresen törli a régieket",
+                    value: "{new_alternatives}",
+                    oninput: move |e| new_alternatives.set(e.value()),
+                }
+            }
+            label { class: "flex items-center gap-1 text-sm",
+                input {
+                    r#type: "checkbox",
+                    checked: rotate_secret(),
+                    onchange: move |e| rotate_secret.set(e.checked()),
+                }
+                "csapatonkénti válaszok új titokkal (minden csapat válasza megváltozik)"
+            }
+            div { class: "flex items-center gap-2 text-sm",
+                label { class: "flex items-center gap-1",
+                    input {
+                        r#type: "checkbox",
+                        checked: keep_history(),
+                        onchange: move |e| keep_history.set(e.checked()),
+                    }
+                    "a csapatok megoldásai maradjanak meg előzményként (az azonosító nem használható újra)"
+                }
+                button {
+                    class: "ml-auto rounded border border-red-600 px-3 py-1 text-red-600 disabled:opacity-50",
+                    disabled: pending(),
+                    onclick: on_delete,
+                    "törlés"
+                }
+            }
+            match outcome() {
+                Some(Ok(msg)) => rsx! {
+                    p { class: "text-green-700", "{msg}" }
+                },
+                Some(Err(msg)) => rsx! {
+                    p { class: "text-red-600", "{msg}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
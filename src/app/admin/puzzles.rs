@@ -0,0 +1,595 @@
+use crate::app::{difficulty_label, error_msg};
+use crate::backend::endpoints::set_solution;
+use crate::backend::models::*;
+use dioxus::prelude::*;
+use std::collections::HashSet;
+
+/// a puzzle being authored, raw user input
+#[derive(Clone, Default, PartialEq)]
+struct PuzzleRow {
+    id: String,
+    value: String,
+    /// static scoring if `None`
+    curve: Option<DecayCurve>,
+    minimum: String,
+    decay_solves: String,
+    /// comma separated, for the first, second, ... solver
+    bonuses: String,
+    /// server-wide rules for wrong attempts if `false`
+    own_rules: bool,
+    penalty: String,
+    cooldown_after: String,
+    cooldown_secs: String,
+    max_attempts: String,
+    solution: PuzzleSolution,
+    /// other accepted solutions, one per line
+    alternatives: String,
+    normalization: Normalization,
+    /// `solution` is the number, the pattern, the program or the template unless it's [`Check::Hashed`]
+    check: Check,
+    /// ± around the number, for [`Check::Numeric`]
+    tolerance: String,
+    title: String,
+    /// markdown
+    statement: String,
+    /// comma separated
+    tags: String,
+    difficulty: Option<Difficulty>,
+    order: String,
+}
+
+/// kinds of [`AnswerChecker`]s
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Check {
+    #[default]
+    Hashed,
+    Numeric,
+    Regex,
+    External,
+    PerTeam,
+}
+
+/// parse `<select>` values of [`Check`]s
+fn parse_check(value: &str) -> Check {
+    match value {
+        "numeric" => Check::Numeric,
+        "regex" => Check::Regex,
+        "external" => Check::External,
+        "per_team" => Check::PerTeam,
+        _ => Check::Hashed,
+    }
+}
+
+impl PuzzleRow {
+    /// the checker and the solutions to send with it, the pattern, the program and the template are only checked by the server
+    fn checker(&self) -> Option<(AnswerChecker, PuzzleSolution, Vec<PuzzleSolution>)> {
+        match self.check {
+            Check::Hashed => {
+                let alternatives = (self.alternatives.lines())
+                    .filter(|alternative| !alternative.trim().is_empty())
+                    .map(String::from)
+                    .collect();
+                Some((AnswerChecker::Hashed, self.solution.clone(), alternatives))
+            }
+            Check::Numeric => {
+                let parse = |field: &str| field.trim().replace(',', ".").parse::<f64>().ok();
+                let number = parse(&self.solution).filter(|number| number.is_finite())?;
+                let tolerance = match self.tolerance.trim() {
+                    "" => 0.,
+                    tolerance => parse(tolerance).filter(|tolerance| *tolerance >= 0.)?,
+                };
+                let checker = AnswerChecker::NumericRange {
+                    min: number - tolerance,
+                    max: number + tolerance,
+                };
+                Some((checker, String::new(), vec![]))
+            }
+            Check::Regex => {
+                let pattern = self.solution.clone();
+                Some((AnswerChecker::Regex { pattern }, String::new(), vec![]))
+            }
+            Check::External => {
+                let program = self.solution.trim().to_string();
+                Some((AnswerChecker::External { program }, String::new(), vec![]))
+            }
+            Check::PerTeam => {
+                let template = match self.solution.trim() {
+                    "" => HMAC_PLACEHOLDER.to_string(),
+                    template => template.to_string(),
+                };
+                // generated by the server
+                let secret = String::new();
+                let checker = AnswerChecker::PerTeam { secret, template };
+                Some((checker, String::new(), vec![]))
+            }
+        }
+    }
+
+    /// the puzzle's own rules for wrong attempts, empty fields are 0, i.e. off
+    fn attempt_rules(&self) -> Result<Option<AttemptRules>, std::num::ParseIntError> {
+        if !self.own_rules {
+            return Ok(None);
+        }
+        fn or_zero(field: &str) -> &str {
+            match field.trim() {
+                "" => "0",
+                trimmed => trimmed,
+            }
+        }
+        Ok(Some(AttemptRules {
+            penalty: or_zero(&self.penalty).parse()?,
+            cooldown_after: or_zero(&self.cooldown_after).parse()?,
+            cooldown_secs: or_zero(&self.cooldown_secs).parse()?,
+            max_attempts: or_zero(&self.max_attempts).parse()?,
+        }))
+    }
+}
+
+/// parse `<select>` values of [`DecayCurve`]s
+fn parse_curve(value: &str) -> Option<DecayCurve> {
+    match value {
+        "linear" => Some(DecayCurve::Linear),
+        "quadratic" => Some(DecayCurve::Quadratic),
+        _ => None,
+    }
+}
+
+/// parse `<select>` values of [`Difficulty`]s
+fn parse_difficulty(value: &str) -> Option<Difficulty> {
+    match value {
+        "easy" => Some(Difficulty::Easy),
+        "medium" => Some(Difficulty::Medium),
+        "hard" => Some(Difficulty::Hard),
+        _ => None,
+    }
+}
+
+/// check rows locally, so the whole batch isn't rejected by the server for a typo
+///
+/// returns the puzzles to send, or the problems found with the rows
+fn validate(rows: &[PuzzleRow]) -> Result<PuzzleSolutions, Vec<String>> {
+    let mut problems = vec![];
+    let mut seen_ids = HashSet::new();
+    let mut puzzle_solutions = PuzzleSolutions::new();
+
+    for (i, row) in rows.iter().enumerate() {
+        let n = i + 1;
+        let id = row.id.trim();
+        if id.is_empty() {
+            problems.push(format!("{n}. sor: hiányzik az azonosító"));
+        } else if !seen_ids.insert(id) {
+            problems.push(format!("{n}. sor: ismétlődő azonosító: {id:?}"));
+        }
+        if row.solution.trim().is_empty() {
+            problems.push(format!("{n}. sor: üres megoldás"));
+        }
+        let Ok(value) = row.value.trim().parse::<PuzzleValue>() else {
+            problems.push(format!("{n}. sor: érvénytelen pontérték: {:?}", row.value));
+            continue;
+        };
+
+        let decay = match row.curve {
+            None => None,
+            Some(curve) => {
+                let minimum = row.minimum.trim().parse::<PuzzleValue>();
+                let solves = row.decay_solves.trim().parse::<u32>();
+                match (minimum, solves) {
+                    (Ok(minimum), Ok(solves)) if minimum <= value && solves > 0 => Some(Decay {
+                        minimum,
+                        solves,
+                        curve,
+                    }),
+                    (Ok(minimum), Ok(_)) if minimum > value => {
+                        problems.push(format!("{n}. sor: a minimum több, mint a kezdőérték"));
+                        continue;
+                    }
+                    _ => {
+                        problems.push(format!(
+                            "{n}. sor: érvénytelen minimum vagy lecsengés: {:?}, {:?}",
+                            row.minimum, row.decay_solves
+                        ));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let bonuses = (row.bonuses.split(',').map(str::trim))
+            .filter(|bonus| !bonus.is_empty())
+            .map(str::parse::<PuzzleValue>)
+            .collect::<Result<Vec<_>, _>>();
+        let Ok(bonuses) = bonuses else {
+            problems.push(format!("{n}. sor: érvénytelen bónuszok: {:?}", row.bonuses));
+            continue;
+        };
+
+        let Ok(attempt_rules) = row.attempt_rules() else {
+            problems.push(format!("{n}. sor: érvénytelen hibaszabályok"));
+            continue;
+        };
+
+        let order = match row.order.trim() {
+            "" => Ok(0),
+            order => order.parse::<i32>(),
+        };
+        let Ok(order) = order else {
+            problems.push(format!("{n}. sor: érvénytelen sorrend: {:?}", row.order));
+            continue;
+        };
+        let meta = PuzzleMeta {
+            title: row.title.trim().to_string(),
+            statement: row.statement.clone(),
+            tags: (row.tags.split(',').map(str::trim))
+                .filter(|tag| !tag.is_empty())
+                .map(String::from)
+                .collect(),
+            difficulty: row.difficulty,
+            order,
+        };
+
+        let Some((checker, solution, alternatives)) = row.checker() else {
+            problems.push(format!(
+                "{n}. sor: érvénytelen szám vagy tűrés: {:?}, {:?}",
+                row.solution, row.tolerance
+            ));
+            continue;
+        };
+
+        let puzzle = Puzzle {
+            solution,
+            alternatives,
+            checker,
+            value,
+            decay,
+            bonuses,
+            attempt_rules,
+            normalization: row.normalization,
+            meta,
+        };
+        _ = puzzle_solutions.insert(id.to_string(), puzzle);
+    }
+
+    if problems.is_empty() {
+        Ok(puzzle_solutions)
+    } else {
+        Err(problems)
+    }
+}
+
+/// add new puzzles in one batch with [`set_solution`]
+#[component]
+pub fn PuzzleEditor(on_saved: EventHandler<()>) -> Element {
+    let mut rows = use_signal(|| vec![PuzzleRow::default()]);
+    let mut pending = use_signal(|| false);
+    let mut outcome = use_signal(|| None::<Result<String, Vec<String>>>);
+
+    let on_submit = move |evt: FormEvent| async move {
+        evt.prevent_default();
+        let puzzle_solutions = match validate(&rows.read()) {
+            Ok(puzzle_solutions) => puzzle_solutions,
+            Err(problems) => {
+                outcome.set(Some(Err(problems)));
+                return;
+            }
+        };
+
+        pending.set(true);
+        outcome.set(None);
+        match set_solution(puzzle_solutions).await {
+            Ok(msg) => {
+                rows.set(vec![PuzzleRow::default()]);
+                outcome.set(Some(Ok(msg)));
+                on_saved.call(());
+            }
+            // lists clashing ids, if any, as the whole batch was rejected
+            Err(e) => outcome.set(Some(Err(vec![error_msg(&e)]))),
+        }
+        pending.set(false);
+    };
+
+    rsx! {
+        h2 { class: "text-xl font-bold", "új feladatok" }
+        form { class: "flex flex-col gap-2", onsubmit: on_submit,
+            p { class: "text-sm text-slate-500",
+                "csökkenő pontozásnál a feladat a kezdő pontértéket éri, majd a megadott számú további megoldás után már csak a minimumot, mindenkinek"
+            }
+            p { class: "text-sm text-slate-500",
+                "bónuszpontot az elsőként megoldó csapatok kapnak, vesszővel elválasztva sorrendben, pl.: 10, 5, 2"
+            }
+            div { class: "grid grid-cols-[1fr_5rem_7rem_5rem_5rem_6rem_2fr_auto] gap-2",
+                span { "azonosító" }
+                span { "pont" }
+                span { "pontozás" }
+                span { "minimum" }
+                span { "lecsengés" }
+                span { "bónuszok" }
+                span { "megoldás" }
+                span {}
+                for (i , row) in rows().into_iter().enumerate() {
+                    input {
+                        class: "rounded border px-2 py-1 font-mono",
+                        value: "{row.id}",
+                        oninput: move |e| rows.write()[i].id = e.value(),
+                    }
+                    input {
+                        class: "rounded border px-2 py-1",
+                        r#type: "number",
+                        min: "0",
+                        value: "{row.value}",
+                        oninput: move |e| rows.write()[i].value = e.value(),
+                    }
+                    select {
+                        class: "rounded border px-2 py-1",
+                        onchange: move |e| rows.write()[i].curve = parse_curve(&e.value()),
+                        option { value: "static", selected: row.curve.is_none(), "állandó" }
+                        option {
+                            value: "linear",
+                            selected: row.curve == Some(DecayCurve::Linear),
+                            "lineáris"
+                        }
+                        option {
+                            value: "quadratic",
+                            selected: row.curve == Some(DecayCurve::Quadratic),
+                            "négyzetes"
+                        }
+                    }
+                    input {
+                        class: "rounded border px-2 py-1 disabled:opacity-50",
+                        r#type: "number",
+                        min: "0",
+                        disabled: row.curve.is_none(),
+                        value: "{row.minimum}",
+                        oninput: move |e| rows.write()[i].minimum = e.value(),
+                    }
+                    input {
+                        class: "rounded border px-2 py-1 disabled:opacity-50",
+                        r#type: "number",
+                        min: "1",
+                        title: "ennyi további megoldás után éri a minimumot",
+                        disabled: row.curve.is_none(),
+                        value: "{row.decay_solves}",
+                        oninput: move |e| rows.write()[i].decay_solves = e.value(),
+                    }
+                    input {
+                        class: "rounded border px-2 py-1",
+                        placeholder: "10, 5",
+                        value: "{row.bonuses}",
+                        oninput: move |e| rows.write()[i].bonuses = e.value(),
+                    }
+                    input {
+                        class: "rounded border px-2 py-1",
+                        autocomplete: "off",
+                        value: "{row.solution}",
+                        oninput: move |e| rows.write()[i].solution = e.value(),
+                    }
+                    button {
+                        class: "rounded border px-2",
+                        r#type: "button",
+                        title: "sor törlése",
+                        onclick: move |_| _ = rows.write().remove(i),
+                        "×"
+                    }
+                    div { class: "col-span-full flex flex-wrap items-center gap-2 text-sm",
+                        input {
+                            class: "flex-1 rounded border px-2 py-1",
+                            placeholder: "cím",
+                            value: "{row.title}",
+                            oninput: move |e| rows.write()[i].title = e.value(),
+                        }
+                        input {
+                            class: "w-40 rounded border px-2 py-1",
+                            placeholder: "kategóriák: web, kripto",
+                            value: "{row.tags}",
+                            oninput: move |e| rows.write()[i].tags = e.value(),
+                        }
+                        select {
+                            class: "rounded border px-2 py-1",
+                            onchange: move |e| rows.write()[i].difficulty = parse_difficulty(&e.value()),
+                            option { value: "", selected: row.difficulty.is_none(), "nehézség" }
+                            for (value , difficulty) in [
+                                ("easy", Difficulty::Easy),
+                                ("medium", Difficulty::Medium),
+                                ("hard", Difficulty::Hard),
+                            ]
+                            {
+                                option {
+                                    value,
+                                    selected: row.difficulty == Some(difficulty),
+                                    "{difficulty_label(difficulty)}"
+                                }
+                            }
+                        }
+                        input {
+                            class: "w-20 rounded border px-2 py-1",
+                            r#type: "number",
+                            placeholder: "sorrend",
+                            title: "a feladatok e szerint növekvő sorrendben jelennek meg",
+                            value: "{row.order}",
+                            oninput: move |e| rows.write()[i].order = e.value(),
+                        }
+                    }
+                    textarea {
+                        class: "col-span-full rounded border px-2 py-1 text-sm",
+                        rows: "3",
+                        placeholder: "feladatleírás (markdown)",
+                        value: "{row.statement}",
+                        oninput: move |e| rows.write()[i].statement = e.value(),
+                    }
+                    div { class: "col-span-full flex items-start gap-2 text-sm",
+                        select {
+                            class: "rounded border px-2 py-1",
+                            title: "a megoldás mezőbe a számot, a mintát, a program útvonalát vagy a sablont írd",
+                            onchange: move |e| rows.write()[i].check = parse_check(&e.value()),
+                            option { value: "hashed", selected: row.check == Check::Hashed, "pontos egyezés" }
+                            option { value: "numeric", selected: row.check == Check::Numeric, "szám" }
+                            option { value: "regex", selected: row.check == Check::Regex, "minta (regex)" }
+                            option { value: "external", selected: row.check == Check::External, "külső program" }
+                            option { value: "per_team", selected: row.check == Check::PerTeam, "csapatonként egyedi" }
+                        }
+                        match row.check {
+                            Check::Hashed => rsx! {
+                                textarea {
+                                    class: "flex-1 rounded border px-2 py-1",
+                                    rows: "2",
+                                    placeholder: "további elfogadott megoldások, soronként egy",
+                                    value: "{row.alternatives}",
+                                    oninput: move |e| rows.write()[i].alternatives = e.value(),
+                                }
+                            },
+                            Check::Numeric => rsx! {
+                                input {
+                                    class: "w-32 rounded border px-2 py-1",
+                                    placeholder: "± tűrés",
+                                    title: "ennyivel térhet el a válasz a megoldástól",
+                                    value: "{row.tolerance}",
+                                    oninput: move |e| rows.write()[i].tolerance = e.value(),
+                                }
+                            },
+                            Check::Regex => rsx! {
+                                span { class: "py-1 text-slate-500",
+                                    "a teljes válasznak illeszkednie kell a mintára"
+                                }
+                            },
+                            Check::External => rsx! {
+                                span { class: "py-1 text-slate-500",
+                                    "a szerveren futó program a bemenetén kapja a választ, és 0-s kóddal kilépve fogadja el"
+                                }
+                            },
+                            Check::PerTeam => rsx! {
+                                span { class: "py-1 text-slate-500",
+                                    "a sablonban a(z) {HMAC_PLACEHOLDER} helyére minden csapatnak más kerül, pl.: flag{{{HMAC_PLACEHOLDER}}}, a válaszokat lent exportálhatod"
+                                }
+                            },
+                        }
+                    }
+                    div { class: "col-span-full flex flex-wrap items-center gap-2 text-sm",
+                        label { class: "flex items-center gap-1",
+                            input {
+                                r#type: "checkbox",
+                                checked: row.own_rules,
+                                onchange: move |e| rows.write()[i].own_rules = e.checked(),
+                            }
+                            "saját hibaszabályok"
+                        }
+                        if row.own_rules {
+                            input {
+                                class: "w-24 rounded border px-2 py-1",
+                                r#type: "number",
+                                min: "0",
+                                placeholder: "levonás",
+                                title: "ennyi pontot vonunk le hibás próbálkozásonként",
+                                value: "{row.penalty}",
+                                oninput: move |e| rows.write()[i].penalty = e.value(),
+                            }
+                            input {
+                                class: "w-24 rounded border px-2 py-1",
+                                r#type: "number",
+                                min: "0",
+                                placeholder: "várakoztat",
+                                title: "ennyi hibás próbálkozásonként várnia kell a csapatnak",
+                                value: "{row.cooldown_after}",
+                                oninput: move |e| rows.write()[i].cooldown_after = e.value(),
+                            }
+                            input {
+                                class: "w-24 rounded border px-2 py-1",
+                                r#type: "number",
+                                min: "0",
+                                placeholder: "mp",
+                                title: "ennyi másodpercet",
+                                value: "{row.cooldown_secs}",
+                                oninput: move |e| rows.write()[i].cooldown_secs = e.value(),
+                            }
+                            input {
+                                class: "w-24 rounded border px-2 py-1",
+                                r#type: "number",
+                                min: "0",
+                                placeholder: "max",
+                                title: "ennyi hibás próbálkozás után lezárjuk a feladatot a csapatnak",
+                                value: "{row.max_attempts}",
+                                oninput: move |e| rows.write()[i].max_attempts = e.value(),
+                            }
+                        } else {
+                            span { class: "text-slate-500", "(a szerver beállításai szerint)" }
+                        }
+                    }
+                    div {
+                        class: "col-span-full flex flex-wrap items-center gap-3 text-sm",
+                        title: "a megoldásokat és a beküldött válaszokat is így egységesítjük, később nem módosítható",
+                        span { "egységesítés:" }
+                        label { class: "flex items-center gap-1",
+                            input {
+                                r#type: "checkbox",
+                                checked: row.normalization.trim,
+                                onchange: move |e| rows.write()[i].normalization.trim = e.checked(),
+                            }
+                            "szélső szóközök levágása"
+                        }
+                        label { class: "flex items-center gap-1",
+                            input {
+                                r#type: "checkbox",
+                                checked: row.normalization.case_fold,
+                                onchange: move |e| rows.write()[i].normalization.case_fold = e.checked(),
+                            }
+                            "kis- és nagybetű mindegy"
+                        }
+                        label { class: "flex items-center gap-1",
+                            input {
+                                r#type: "checkbox",
+                                checked: row.normalization.nfc,
+                                onchange: move |e| rows.write()[i].normalization.nfc = e.checked(),
+                            }
+                            "unicode NFC"
+                        }
+                        label { class: "flex items-center gap-1",
+                            input {
+                                r#type: "checkbox",
+                                checked: row.normalization.collapse_whitespace,
+                                onchange: move |e| rows.write()[i].normalization.collapse_whitespace = e.checked(),
+                            }
+                            "több szóköz helyett egy"
+                        }
+                        label { class: "flex items-center gap-1",
+                            input {
+                                r#type: "checkbox",
+                                checked: row.normalization.strip_accents,
+                                onchange: move |e| rows.write()[i].normalization.strip_accents = e.checked(),
+                            }
+                            "ékezetek elhagyása"
+                        }
+                    }
+                }
+            }
+            button {
+                class: "rounded border px-3 py-1 self-start",
+                r#type: "button",
+                onclick: move |_| rows.write().push(PuzzleRow::default()),
+                "+ új sor"
+            }
+
+            button {
+                class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50",
+                r#type: "submit",
+                disabled: pending() || rows.read().is_empty(),
+                if pending() {
+                    "mentés..."
+                } else {
+                    "mentés"
+                }
+            }
+        }
+
+        match outcome() {
+            Some(Ok(msg)) => rsx! {
+                p { class: "text-green-700", "{msg}" }
+            },
+            Some(Err(problems)) => rsx! {
+                ul { class: "text-red-600 list-disc pl-5",
+                    for problem in problems {
+                        li { "{problem}" }
+                    }
+                }
+            },
+            None => rsx! {},
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::app::{error_msg, format_time};
+use crate::backend::endpoints::submissions;
+use crate::backend::models::*;
+use dioxus::prelude::*;
+
+/// every submission attempt, newest first, to settle disputes and spot cheating
+#[component]
+pub fn SubmissionHistory() -> Element {
+    let mut log = use_signal(|| None::<SubmissionLog>);
+    let mut team_filter = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut pending = use_signal(|| false);
+
+    let on_load = move |_| async move {
+        pending.set(true);
+        match submissions().await {
+            Ok(submissions) => {
+                error.set(None);
+                log.set(Some(submissions));
+            }
+            Err(e) => error.set(Some(error_msg(&e))),
+        }
+        pending.set(false);
+    };
+
+    let shown = log()
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .filter(|submission| submission.team.contains(team_filter().trim()))
+        .collect::<Vec<_>>();
+
+    rsx! {
+        h2 { class: "text-xl font-bold", "beküldések" }
+        button {
+            class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50 self-start",
+            disabled: pending(),
+            onclick: on_load,
+            if log().is_some() {
+                "frissítés"
+            } else {
+                "betöltés"
+            }
+        }
+        if let Some(error) = error() {
+            p { class: "text-red-600", "{error}" }
+        }
+
+        if log().is_some() {
+            input {
+                class: "rounded border px-3 py-1",
+                placeholder: "szűrés csapatra",
+                value: "{team_filter}",
+                oninput: move |e| team_filter.set(e.value()),
+            }
+            table { class: "text-sm",
+                thead {
+                    tr {
+                        th { class: "p-1 text-left", "idő" }
+                        th { class: "p-1 text-left", "csapat" }
+                        th { class: "p-1 text-left", "feladat" }
+                        th { class: "p-1", "" }
+                        th { class: "p-1 text-left", "munkamenet" }
+                        th { class: "p-1 text-left", "ip" }
+                    }
+                }
+                tbody {
+                    for submission in shown {
+                        tr { class: "border-t",
+                            td { class: "p-1", "{format_time(submission.time)}" }
+                            td { class: "p-1", "{submission.team}" }
+                            td { class: "p-1 font-mono", "{submission.puzzle_id}" }
+                            td { class: "p-1",
+                                if submission.correct {
+                                    span { class: "text-green-700", "✓" }
+                                } else {
+                                    span { class: "text-red-600", "✗" }
+                                }
+                                if let Some(rejected) = submission.rejected {
+                                    span { class: "text-slate-500", " {rejection_label(rejected)}" }
+                                }
+                                if let Some(owner) = &submission.shared_from {
+                                    span {
+                                        class: "text-amber-600",
+                                        title: "a(z) {owner} csapat válasza",
+                                        " ⚠ {owner}"
+                                    }
+                                }
+                            }
+                            td { class: "p-1 font-mono", "{submission.session:.8}" }
+                            td { class: "p-1 font-mono", "{submission.ip}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// why it wasn't even checked
+fn rejection_label(rejected: Rejection) -> &'static str {
+    match rejected {
+        Rejection::UnknownPuzzle => "nincs ilyen feladat",
+        Rejection::AlreadySolved => "már megoldották",
+        Rejection::Locked => "elfogytak a próbálkozások",
+        Rejection::Cooldown => "várakozniuk kellett",
+        Rejection::CheckFailed => "nem sikerült ellenőrizni",
+    }
+}
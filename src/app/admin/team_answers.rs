@@ -0,0 +1,83 @@
+use crate::app::error_msg;
+use crate::backend::endpoints::team_answers;
+use crate::backend::models::*;
+use dioxus::prelude::*;
+
+/// each team's own answer to the puzzles with per-team answers, to hand out with the puzzles
+#[component]
+pub fn TeamAnswersExport() -> Element {
+    let mut answers = use_signal(|| None::<TeamAnswers>);
+    // one per line, besides the teams that already joined
+    let mut extra_teams = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut pending = use_signal(|| false);
+
+    let on_load = move |_| async move {
+        pending.set(true);
+        let teams = (extra_teams.read().lines())
+            .map(str::trim)
+            .filter(|team| !team.is_empty())
+            .map(String::from)
+            .collect();
+        match team_answers(teams).await {
+            Ok(loaded) => {
+                error.set(None);
+                answers.set(Some(loaded));
+            }
+            Err(e) => error.set(Some(error_msg(&e))),
+        }
+        pending.set(false);
+    };
+
+    let mut shown = answers()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(puzzle_id, answers)| {
+            let mut answers = answers.into_iter().collect::<Vec<_>>();
+            answers.sort();
+            (puzzle_id, answers)
+        })
+        .collect::<Vec<_>>();
+    shown.sort();
+
+    rsx! {
+        h2 { class: "text-xl font-bold", "csapatonkénti válaszok" }
+        textarea {
+            class: "rounded border px-2 py-1 text-sm",
+            rows: "2",
+            placeholder: "még be nem lépett csapatok nevei, soronként egy",
+            value: "{extra_teams}",
+            oninput: move |e| extra_teams.set(e.value()),
+        }
+        button {
+            class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50 self-start",
+            disabled: pending(),
+            onclick: on_load,
+            if answers().is_some() {
+                "frissítés"
+            } else {
+                "betöltés"
+            }
+        }
+        if let Some(error) = error() {
+            p { class: "text-red-600", "{error}" }
+        }
+
+        if answers().is_some() && shown.is_empty() {
+            p { class: "text-slate-500", "nincs csapatonként egyedi válaszú feladat" }
+        }
+        for (puzzle_id , answers) in shown {
+            h3 { class: "font-bold font-mono", "{puzzle_id}" }
+            table { class: "text-sm",
+                tbody {
+                    for (team , answer) in answers {
+                        tr { class: "border-t",
+                            td { class: "p-1", "{team}" }
+                            td { class: "p-1 font-mono select-all", "{answer}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
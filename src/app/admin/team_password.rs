@@ -0,0 +1,65 @@
+use crate::app::error_msg;
+use crate::backend::endpoints::admin_set_team_password;
+use dioxus::prelude::*;
+
+/// set a team's password, eg: one from an old state file that has none, or one that forgot it
+#[component]
+pub fn TeamPassword() -> Element {
+    let mut team = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut pending = use_signal(|| false);
+    let mut outcome = use_signal(|| None::<Result<String, String>>);
+
+    let on_submit = move |evt: FormEvent| async move {
+        evt.prevent_default();
+        if team().trim().is_empty() || password().is_empty() {
+            outcome.set(Some(Err(String::from("minden mezőt ki kell tölteni"))));
+            return;
+        }
+
+        pending.set(true);
+        outcome.set(None);
+        // the password isn't kept around after setting it
+        let password = password.replace(String::new());
+        match admin_set_team_password(team().trim().to_string(), password).await {
+            Ok(msg) => outcome.set(Some(Ok(msg))),
+            Err(e) => outcome.set(Some(Err(error_msg(&e)))),
+        }
+        pending.set(false);
+    };
+
+    rsx! {
+        h2 { class: "text-xl font-bold", "csapatjelszó" }
+        form { class: "flex gap-2", onsubmit: on_submit,
+            input {
+                class: "flex-1 rounded border px-3 py-1",
+                placeholder: "csapatnév",
+                value: "{team}",
+                oninput: move |e| team.set(e.value()),
+            }
+            input {
+                class: "flex-1 rounded border px-3 py-1",
+                r#type: "password",
+                placeholder: "új csapatjelszó",
+                autocomplete: "new-password",
+                value: "{password}",
+                oninput: move |e| password.set(e.value()),
+            }
+            button {
+                class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50",
+                r#type: "submit",
+                disabled: pending(),
+                "beállítás"
+            }
+        }
+        match outcome() {
+            Some(Ok(msg)) => rsx! {
+                p { class: "text-green-700", "{msg}" }
+            },
+            Some(Err(msg)) => rsx! {
+                p { class: "text-red-600", "{msg}" }
+            },
+            None => rsx! {},
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use super::{AuthState, Route, error_msg, format_time};
+use crate::backend::endpoints::{join, logout, revoke_session, sessions};
+use dioxus::prelude::*;
+
+/// contestant landing page: join the event as a team, or see who you're logged in as
+#[component]
+pub fn Join() -> Element {
+    let mut auth = use_context::<AuthState>();
+
+    let mut username = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut pending = use_signal(|| false);
+
+    let on_join = move |evt: FormEvent| async move {
+        evt.prevent_default();
+        let name = username().trim().to_string();
+        if name.is_empty() {
+            error.set(Some(String::from("add meg a csapatod nevét")));
+            return;
+        }
+        if password().is_empty() {
+            error.set(Some(String::from("add meg a csapatod jelszavát")));
+            return;
+        }
+
+        pending.set(true);
+        match join(name, password()).await {
+            Ok(_) => {
+                error.set(None);
+                username.write().clear();
+                password.write().clear();
+                auth.restart();
+            }
+            Err(e) => error.set(Some(error_msg(&e))),
+        }
+        pending.set(false);
+    };
+
+    let on_logout = move |_| async move {
+        match logout(None).await {
+            Ok(_) => {
+                error.set(None);
+                auth.restart();
+            }
+            Err(e) => error.set(Some(error_msg(&e))),
+        }
+    };
+
+    rsx! {
+        main { class: "mx-auto max-w-md p-6 flex flex-col gap-4",
+            h1 { class: "text-3xl font-bold", "csatlakozás" }
+
+            match auth() {
+                None => rsx! {
+                    p { class: "text-slate-500", "betöltés..." }
+                },
+                Some(Ok(team)) => rsx! {
+                    p {
+                        "be vagy lépve, mint "
+                        strong { "{team}" }
+                    }
+                    Link { class: "underline", to: Route::Puzzles {}, "tovább a feladatokhoz" }
+                    button {
+                        class: "rounded border px-3 py-1 self-start",
+                        onclick: on_logout,
+                        "kilépés"
+                    }
+                    Sessions {}
+                },
+                Some(Err(_)) => rsx! {
+                    form { class: "flex flex-col gap-2", onsubmit: on_join,
+                        label { r#for: "username", "csapatnév" }
+                        input {
+                            id: "username",
+                            class: "rounded border px-3 py-1",
+                            autocomplete: "off",
+                            value: "{username}",
+                            oninput: move |e| username.set(e.value()),
+                        }
+                        label { r#for: "password", "csapatjelszó" }
+                        input {
+                            id: "password",
+                            class: "rounded border px-3 py-1",
+                            r#type: "password",
+                            autocomplete: "current-password",
+                            value: "{password}",
+                            oninput: move |e| password.set(e.value()),
+                        }
+                        p { class: "text-sm text-slate-500",
+                            "új csapatnál az első csatlakozáskor megadott jelszóval léphettek be újra"
+                        }
+                        button {
+                            class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50",
+                            r#type: "submit",
+                            disabled: pending(),
+                            if pending() {
+                                "csatlakozás..."
+                            } else {
+                                "csatlakozás"
+                            }
+                        }
+                    }
+                },
+            }
+
+            if let Some(error) = error() {
+                p { class: "text-red-600", "{error}" }
+            }
+        }
+    }
+}
+
+/// the logged-in devices of the team, any of the others can be logged out
+#[component]
+fn Sessions() -> Element {
+    let mut sessions = use_resource(sessions);
+    let mut error = use_signal(|| None::<String>);
+
+    let on_revoke = move |session_id: String| async move {
+        match revoke_session(session_id).await {
+            Ok(_) => {
+                error.set(None);
+                sessions.restart();
+            }
+            Err(e) => error.set(Some(error_msg(&e))),
+        }
+    };
+
+    rsx! {
+        h2 { class: "text-xl font-bold", "a csapat munkamenetei" }
+        match sessions() {
+            None => rsx! {
+                p { class: "text-slate-500", "betöltés..." }
+            },
+            Some(Err(e)) => rsx! {
+                p { class: "text-red-600", "{error_msg(&e)}" }
+            },
+            Some(Ok(sessions)) => rsx! {
+                ul { class: "flex flex-col gap-1",
+                    for session in sessions {
+                        li { key: "{session.id}", class: "flex items-center gap-2",
+                            code { class: "text-sm", "{session.id:.8}" }
+                            span { class: "text-sm text-slate-500",
+                                "belépett {format_time(session.created_at)}, utoljára aktív {format_time(session.last_seen)}"
+                            }
+                            if session.current {
+                                span { class: "text-slate-500", "(ez az eszköz)" }
+                            } else {
+                                button {
+                                    class: "rounded border px-2 text-sm",
+                                    onclick: move |_| on_revoke(session.id.clone()),
+                                    "kiléptetés"
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        }
+        if let Some(error) = error() {
+            p { class: "text-red-600", "{error}" }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use super::{AuthState, Route};
+use crate::backend::endpoints::{auth_state, event_title};
+use dioxus::prelude::*;
+
+/// shared frame of every page: event title, navigation and the logged-in team
+#[component]
+pub fn Layout() -> Element {
+    let title = use_resource(event_title);
+    // `sid` cookie is `HttpOnly`, so we ask the server who we are, this survives page reloads
+    let auth: AuthState = use_resource(auth_state);
+    use_context_provider(|| auth);
+
+    let title = match title() {
+        Some(Ok(title)) => title,
+        _ => String::from("Apollo"),
+    };
+
+    rsx! {
+        document::Title { "{title}" }
+
+        header { class: "flex flex-wrap items-center gap-4 border-b bg-white px-6 py-3",
+            Link { class: "text-xl font-bold", to: Route::Join {}, "{title}" }
+            nav { class: "flex gap-3",
+                Link { class: "underline", to: Route::Puzzles {}, "feladatok" }
+                Link { class: "underline", to: Route::Scoreboard {}, "eredmények" }
+                Link { class: "underline", to: Route::Admin {}, "admin" }
+            }
+            span { class: "ml-auto text-slate-500",
+                if let Some(Ok(team)) = auth() {
+                    "csapat: "
+                    strong { class: "text-slate-900", "{team}" }
+                } else {
+                    "nem vagy bejelentkezve"
+                }
+            }
+        }
+
+        Outlet::<Route> {}
+    }
+}
+
+/// 404
+#[component]
+pub fn NotFound(segments: Vec<String>) -> Element {
+    let path = segments.join("/");
+    rsx! {
+        main { class: "mx-auto max-w-md p-6 flex flex-col gap-2",
+            h1 { class: "text-3xl font-bold", "404" }
+            p { "nincs ilyen oldal: /{path}" }
+            Link { class: "underline", to: Route::Join {}, "vissza a kezdőlapra" }
+        }
+    }
+}
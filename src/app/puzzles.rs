@@ -0,0 +1,163 @@
+use super::{Route, difficulty_label, error_msg};
+use crate::backend::endpoints::{puzzles, submit_solution, team_state};
+use crate::backend::models::*;
+use dioxus::prelude::*;
+
+/// every existing puzzle in order with an answer box, for the logged-in team
+#[component]
+pub fn Puzzles() -> Element {
+    let mut state = use_resource(|| async {
+        let (team, _) = team_state().await?;
+        Ok::<_, HttpError>((team, puzzles().await?))
+    });
+
+    let (team, puzzles) = match state() {
+        None => {
+            return rsx! {
+                main { class: "p-6",
+                    p { class: "text-slate-500", "betöltés..." }
+                }
+            };
+        }
+        Some(Err(e)) => {
+            return rsx! {
+                main { class: "p-6 flex flex-col gap-2",
+                    p { class: "text-red-600", "{error_msg(&e)}" }
+                    Link { class: "underline", to: Route::Join {}, "csatlakozz egy csapattal" }
+                }
+            };
+        }
+        Some(Ok(state)) => state,
+    };
+
+    rsx! {
+        main { class: "mx-auto max-w-2xl p-6 flex flex-col gap-4",
+            h1 { class: "text-3xl font-bold", "feladatok" }
+            if puzzles.is_empty() {
+                p { class: "text-slate-500", "még nincsenek feladatok" }
+            }
+            for puzzle in puzzles {
+                PuzzleCard {
+                    key: "{puzzle.id}",
+                    solved: team.solved.contains_key(&puzzle.id),
+                    wrong_attempts: team.wrong_attempts.get(&puzzle.id).copied(),
+                    puzzle,
+                    on_submitted: move |_| state.restart(),
+                }
+            }
+        }
+    }
+}
+
+/// a single puzzle with its own answer box and submission state
+#[component]
+fn PuzzleCard(
+    puzzle: PuzzleListing,
+    solved: bool,
+    wrong_attempts: Option<WrongAttempts>,
+    on_submitted: EventHandler<()>,
+) -> Element {
+    let mut answer = use_signal(String::new);
+    let mut pending = use_signal(|| false);
+    // success or failure message of the last submission
+    let mut outcome = use_signal(|| None::<Result<String, String>>);
+
+    let PuzzleListing { id, value, meta } = puzzle;
+    let puzzle_id = id.clone();
+    let on_submit = move |evt: FormEvent| {
+        let id = puzzle_id.clone();
+        async move {
+            evt.prevent_default();
+            if answer().trim().is_empty() {
+                outcome.set(Some(Err(String::from("üres megoldást nem küldhetsz be"))));
+                return;
+            }
+
+            pending.set(true);
+            match submit_solution(id, answer()).await {
+                Ok(msg) => {
+                    answer.write().clear();
+                    outcome.set(Some(Ok(msg)));
+                    on_submitted.call(());
+                }
+                Err(e) => {
+                    outcome.set(Some(Err(error_msg(&e))));
+                    // wrong attempts are counted too, and might cost points
+                    on_submitted.call(());
+                }
+            }
+            pending.set(false);
+        }
+    };
+
+    rsx! {
+        section { class: "rounded border bg-white p-4 flex flex-col gap-2",
+            div { class: "flex justify-between",
+                if meta.title.is_empty() {
+                    h2 { class: "font-mono font-bold", "{id}" }
+                } else {
+                    h2 { class: "font-bold", title: "{id}", "{meta.title}" }
+                }
+                span { "{value} pont" }
+            }
+            if meta.difficulty.is_some() || !meta.tags.is_empty() {
+                div { class: "flex flex-wrap gap-1 text-xs",
+                    if let Some(difficulty) = meta.difficulty {
+                        span { class: "rounded bg-slate-900 px-2 text-white",
+                            "{difficulty_label(difficulty)}"
+                        }
+                    }
+                    for tag in meta.tags {
+                        span { class: "rounded border px-2", "{tag}" }
+                    }
+                }
+            }
+            if !meta.statement.is_empty() {
+                // NOTE: markdown, shown as is for now
+                p { class: "whitespace-pre-wrap", "{meta.statement}" }
+            }
+
+            if let Some(wrong) = wrong_attempts.filter(|wrong| wrong.count > 0) {
+                p { class: "text-sm text-slate-500",
+                    "{wrong.count} hibás próbálkozás"
+                    if wrong.penalty > 0 {
+                        ", -{wrong.penalty} pont"
+                    }
+                }
+            }
+            if solved {
+                p { class: "text-green-700", "✓ megoldva" }
+            } else {
+                form { class: "flex gap-2", onsubmit: on_submit,
+                    input {
+                        class: "flex-1 rounded border px-3 py-1",
+                        placeholder: "megoldás",
+                        autocomplete: "off",
+                        value: "{answer}",
+                        oninput: move |e| answer.set(e.value()),
+                    }
+                    button {
+                        class: "rounded bg-slate-900 px-3 py-1 text-white disabled:opacity-50",
+                        r#type: "submit",
+                        disabled: pending(),
+                        if pending() {
+                            "ellenőrzés..."
+                        } else {
+                            "beküldés"
+                        }
+                    }
+                }
+            }
+
+            match outcome() {
+                Some(Ok(msg)) => rsx! {
+                    p { class: "text-green-700", "{msg}" }
+                },
+                Some(Err(msg)) => rsx! {
+                    p { class: "text-red-600", "{msg}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::{format_time, sleep};
+use crate::backend::endpoints::state_stream;
+use crate::backend::models::*;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// how long to wait before reconnecting to a dropped state stream
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// live standings of all the teams, fed by [`state_stream`]
+#[component]
+pub fn Scoreboard() -> Element {
+    let mut state = use_signal(|| None::<(TeamsState, PuzzlesExisting)>);
+    let mut error = use_signal(|| None::<String>);
+
+    // keep (re)connecting for as long as the page is open
+    use_future(move || async move {
+        loop {
+            match state_stream().await {
+                Ok(mut stream) => {
+                    while let Some(update) = stream.next().await {
+                        match update {
+                            Ok(update) => {
+                                error.set(None);
+                                state.set(Some(update));
+                            }
+                            Err(e) => {
+                                error.set(Some(format!("megszakadt a kapcsolat: {e}")));
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => error.set(Some(format!("nem sikerült csatlakozni: {e}"))),
+            }
+            sleep(RECONNECT_DELAY).await;
+        }
+    });
+
+    let Some((teams, puzzles)) = state() else {
+        return rsx! {
+            main { class: "p-6",
+                h1 { class: "text-3xl font-bold", "eredmények" }
+                if let Some(error) = error() {
+                    p { class: "text-red-600", "{error}" }
+                } else {
+                    p { class: "text-slate-500", "betöltés..." }
+                }
+            }
+        };
+    };
+
+    let mut puzzle_ids = puzzles.keys().cloned().collect::<Vec<_>>();
+    puzzle_ids.sort();
+    let standings = rank_teams(&teams, &puzzles);
+
+    rsx! {
+        main { class: "p-6 flex flex-col gap-4",
+            h1 { class: "text-3xl font-bold", "eredmények" }
+            if let Some(error) = error() {
+                p { class: "text-red-600", "{error}, újracsatlakozunk..." }
+            }
+
+            table { class: "border-collapse text-center",
+                thead {
+                    tr {
+                        th { class: "p-2", "#" }
+                        th { class: "p-2 text-left", "csapat" }
+                        th { class: "p-2", "pont" }
+                        th { class: "p-2", title: "az elsők közt beküldött megoldásokért", "bónusz" }
+                        th { class: "p-2", title: "a hibás próbálkozásokért", "levonás" }
+                        for id in puzzle_ids.iter() {
+                            th { class: "p-2 font-mono", title: "{puzzles[id]} pont", "{id}" }
+                        }
+                    }
+                }
+                tbody {
+                    for standing in standings {
+                        tr { class: "border-t",
+                            td { class: "p-2", "{standing.rank}." }
+                            td { class: "p-2 text-left", "{standing.team}" }
+                            td {
+                                class: "p-2 font-bold",
+                                title: standing.last_solve.map(format_time),
+                                "{standing.total}"
+                            }
+                            td { class: "p-2 text-slate-500",
+                                if standing.bonus > 0 {
+                                    "+{standing.bonus}"
+                                }
+                            }
+                            td { class: "p-2 text-slate-500",
+                                if standing.penalty > 0 {
+                                    "-{standing.penalty}"
+                                }
+                            }
+                            for id in puzzle_ids.iter() {
+                                if let Some(solve) = teams[&standing.team].solved.get(id) {
+                                    td { class: "p-2", title: "{format_time(solve.time)}",
+                                        "✓"
+                                        if solve.bonus > 0 {
+                                            sup { class: "text-amber-600", "+{solve.bonus}" }
+                                        }
+                                    }
+                                } else {
+                                    td { class: "p-2" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
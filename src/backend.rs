@@ -3,9 +3,15 @@
 
 pub mod models;
 
+#[cfg(feature = "server")]
+mod checker;
 #[cfg(feature = "server")]
 mod logic;
 #[cfg(feature = "server")]
-pub use logic::INIT_PWD;
+pub use logic::{INIT_PWD, prune_sessions};
+#[cfg(feature = "server")]
+mod rate_limit;
+#[cfg(feature = "server")]
+pub use rate_limit::rate_limit;
 
 pub mod endpoints;
@@ -0,0 +1,156 @@
+use super::logic::env_or;
+use dioxus::prelude::*;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+/// how long a checker may run, waiting in the queue not included
+static TIMEOUT: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_or("APOLLO_CHECKER_TIMEOUT_SECS", 10)));
+/// how much memory a checker may use in MiB, no limits if 0
+///
+/// NOTE: only enforced on linux
+static MEMORY_MB: LazyLock<u64> = LazyLock::new(|| env_or("APOLLO_CHECKER_MEMORY_MB", 256));
+/// how much a checker may write to its stdout and stderr each
+static MAX_OUTPUT: LazyLock<u64> =
+    LazyLock::new(|| env_or("APOLLO_CHECKER_MAX_OUTPUT_BYTES", 64 * 1024));
+/// how many checkers may run at once, the rest wait in line
+static SLOTS: LazyLock<Semaphore> = LazyLock::new(|| {
+    let cpus = std::thread::available_parallelism().map_or(1, usize::from);
+    Semaphore::new(env_or("APOLLO_CHECKER_CONCURRENCY", cpus).max(1))
+});
+
+/// bad request unless `program` is an absolute path to an executable file
+pub(super) fn check_program(program: &str) -> Result<(), HttpError> {
+    let path = Path::new(program);
+    path.is_absolute()
+        .or_bad_request("az ellenőrző program útvonala legyen abszolút")?;
+    let metadata = std::fs::metadata(path).or_bad_request("nem található az ellenőrző program")?;
+    #[cfg(unix)]
+    let is_executable = {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    };
+    #[cfg(not(unix))]
+    let is_executable = true;
+    (metadata.is_file() && is_executable).or_bad_request("az ellenőrző program nem futtatható")
+}
+
+/// run `program` with `answer` on its stdin, it's correct if it exits with 0, wrong with any other code
+///
+/// errors if it couldn't be run, timed out, got killed, eg: for using too much memory, or wrote too much,
+/// none of which is the team's fault
+///
+/// SECURITY: it runs as the server's user, without its environment
+pub(super) async fn run(program: &str, answer: &str) -> Result<bool, HttpError> {
+    let _slot = SLOTS
+        .acquire()
+        .await
+        .or_internal_server_error("nem sikerült ellenőrizni a feladatmegoldást")?;
+
+    let checked = tokio::time::timeout(*TIMEOUT, run_unqueued(program, answer)).await;
+    let failure = match checked {
+        Ok(Ok(is_correct)) => return Ok(is_correct),
+        Ok(Err(failure)) => failure,
+        Err(_) => format!("{}s után sem végzett", TIMEOUT.as_secs()),
+    };
+    error!("a(z) {program:?} ellenőrző program hibázott: {failure}");
+    HttpError::internal_server_error("nem sikerült ellenőrizni a feladatmegoldást")?
+}
+
+/// NOTE: the child, and whatever it started, is killed when this is dropped, eg: on timeout
+async fn run_unqueued(program: &str, answer: &str) -> Result<bool, String> {
+    let mut command = Command::new(program);
+    // NOTE: `prlimit` limits itself, then execs the checker, so it's limited from its very first instruction
+    #[cfg(target_os = "linux")]
+    if *MEMORY_MB > 0 {
+        let bytes = MEMORY_MB.saturating_mul(1024 * 1024);
+        command = Command::new("prlimit");
+        command.arg(format!("--as={bytes}")).arg("--").arg(program);
+    }
+    command
+        .env_clear()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // its own process group, so whatever it starts can be killed along with it
+    #[cfg(unix)]
+    command.process_group(0);
+    if let Some(path) = std::env::var_os("PATH") {
+        command.env("PATH", path);
+    }
+    let mut child = command.spawn().map_err(|e| format!("nem indult el: {e}"))?;
+    #[cfg(unix)]
+    let group = ProcessGroup(child.id());
+
+    let (Some(mut stdin), Some(stdout), Some(stderr)) =
+        (child.stdin.take(), child.stdout.take(), child.stderr.take())
+    else {
+        return Err(String::from("nem érhető el a bemenete vagy a kimenete"));
+    };
+    let write = async move {
+        // NOTE: it may exit without reading all of it, that's up to the checker
+        _ = stdin.write_all(answer.as_bytes()).await;
+        Ok::<_, String>(())
+    };
+    // NOTE: stops reading the other one too, once one writes too much
+    let ((), stdout, stderr) = tokio::try_join!(write, read_limited(stdout), read_limited(stderr))?;
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("nem sikerült megvárni: {e}"))?;
+    #[cfg(unix)]
+    group.reaped();
+    match status.code() {
+        Some(code) => {
+            debug!(
+                "a(z) {program:?} ellenőrző program {code} kóddal végzett, kimenete: {stdout:?}, hibakimenete: {stderr:?}"
+            );
+            Ok(code == 0)
+        }
+        None => Err(format!("leállították ({status}), hibakimenete: {stderr:?}")),
+    }
+}
+
+/// the process group of a checker, killed when dropped, unless its leader has been reaped
+#[cfg(unix)]
+struct ProcessGroup(Option<u32>);
+
+#[cfg(unix)]
+impl ProcessGroup {
+    /// NOTE: the id of the leader may be reused once it's reaped, so the group can't be killed after that
+    fn reaped(mut self) {
+        self.0 = None;
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        use nix::sys::signal::{Signal, killpg};
+        use nix::unistd::Pid;
+
+        if let Some(Ok(pgid)) = self.0.map(i32::try_from) {
+            // NOTE: it just fails if everything in the group has exited already
+            _ = killpg(Pid::from_raw(pgid), Signal::SIGKILL);
+        }
+    }
+}
+
+/// everything from `output`, unless there's more than `MAX_OUTPUT`
+async fn read_limited(output: impl AsyncRead + Unpin) -> Result<String, String> {
+    let mut read = Vec::new();
+    (output.take(*MAX_OUTPUT + 1).read_to_end(&mut read))
+        .await
+        .map_err(|e| format!("nem sikerült olvasni a kimenetét: {e}"))?;
+    if read.len() as u64 > *MAX_OUTPUT {
+        return Err(format!("több mint {} bájtot írt ki", *MAX_OUTPUT));
+    }
+    Ok(String::from_utf8_lossy(&read).into_owned())
+}
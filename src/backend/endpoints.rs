@@ -4,7 +4,9 @@ use dioxus::prelude::*;
 #[cfg(feature = "server")]
 use {
     super::logic::*,
-    dioxus::fullstack::{Cookie, TypedHeader},
+    dioxus::fullstack::{Cookie, HeaderMap, TypedHeader},
+    dioxus::server::axum::extract::ConnectInfo,
+    std::net::SocketAddr,
     uuid::Uuid,
     zeroize::Zeroize,
 };
@@ -25,82 +27,117 @@ pub async fn state_stream() -> Result<Streaming<(TeamsState, PuzzlesExisting), C
     }))
 }
 
+/// every team ranked by score, see [`rank_teams`]
+#[get("/api/standings")]
+pub async fn standings() -> Result<Standings, HttpError> {
+    check_admin_pwd()?;
+    let (teams_state, existing_puzzles) = get_game_state().await;
+    Ok(rank_teams(&teams_state, &existing_puzzles))
+}
+
 /// returns username if valid
 #[get("/api/auth_state", cookies: TypedHeader<Cookie>)]
 pub async fn auth_state() -> Result<String, HttpError> {
     check_admin_pwd()?;
-    let uuid = extract_sid_cookie(cookies).await?;
-    let username = USER_IDS
-        .read()
-        .await
-        .get(&uuid)
-        .or_not_found("nincs ezzel az azonosítóval csapat")?
-        .clone();
+    let (_, username) = authenticate(cookies).await?;
     Ok(username)
 }
 
+/// returns the progress of the team along with all the existing puzzles
+#[get("/api/team_state", cookies: TypedHeader<Cookie>)]
+pub async fn team_state() -> Result<(TeamState, PuzzlesExisting), HttpError> {
+    check_admin_pwd()?;
+    let (_, username) = authenticate(cookies).await?;
+    let (mut teams_state, existing_puzzles) = get_game_state().await;
+    let team_state = teams_state
+        .remove(&username)
+        .or_internal_server_error("nincs ehhez a csapatnévhez előrehaladás rendelve")?;
+    Ok((team_state, existing_puzzles))
+}
+
+/// every existing puzzle in order, with its title, statement and such, see [`PuzzleListing`]
+#[get("/api/puzzles", cookies: TypedHeader<Cookie>)]
+pub async fn puzzles() -> Result<PuzzleList, HttpError> {
+    check_admin_pwd()?;
+    _ = authenticate(cookies).await?;
+    Ok(get_puzzle_list().await)
+}
+
 /// join the competition as a contestant team
 ///
 /// - got `sid` cookie
 ///   - valid => forbidden
 ///   - invalid => goto #no-sid-cookie
 /// - no `sid` cookie
-///   - `username`'s session is taken => forbidden
+///   - `password` doesn't match the one set on the team's first join => unauthorized
+///   - the team has progress but no password, eg: from an old state file => forbidden, see [`set_team_password`]
+///   - `username` already has `MAX_SESSIONS` sessions => forbidden
 ///   - otherwise => allowed, preserve progress if any
 ///
 /// We'll return a `SetCookie` header if the login is successful.
 ///
 /// This will set a cookie in the user's browser that can be used for subsequent authenticated requests.
 #[post("/api/join", cookies: TypedHeader<Cookie>)]
-pub async fn join(username: String) -> Result<SetHeader<SetCookie>, HttpError> {
+pub async fn join(
+    username: String,
+    mut password: String,
+) -> Result<SetHeader<SetCookie>, HttpError> {
     check_admin_pwd()?;
-    if let Ok(sent_uuid) = extract_sid_cookie(cookies).await
-        && USER_IDS.read().await.contains_key(&sent_uuid)
-    {
+    if authenticate(cookies).await.is_ok() {
         return HttpError::forbidden("már be vagy lépve");
     }
 
-    // whether someone's currently logged in to this account: `USER_IDS` contains `username`
-    (!USER_IDS.read().await.values().any(|u| u == &username)).or_forbidden("foglalt munkamenet")?;
+    let password_check = check_team_password(&username, &password).await;
+    password.zeroize();
+    password_check?;
 
-    // brand new team
-    if !TEAMS.read().await.contains_key(&username) {
-        _ = TEAMS
-            .write()
-            .await
-            .insert(username.clone(), SolvedPuzzles::new());
-    }
-    // allowed to log in, but don't reset progress
+    // brand new team, otherwise allowed to log in, but don't reset progress
+    _ = TEAMS.write().await.entry(username.clone()).or_default();
 
     let uuid = Uuid::new_v4();
-    _ = USER_IDS.write().await.insert(uuid, username);
+    // counted and inserted under the same lock, so parallel joins can't go over `MAX_SESSIONS`
+    let mut user_ids = USER_IDS.write().await;
+    // how many members of the team are logged in already
+    let session_count = (user_ids.values())
+        .filter(|session| session.username == username)
+        .count();
+    (session_count < *MAX_SESSIONS).or_forbidden(format!(
+        "foglalt munkamenet, egy csapat legfeljebb {} helyről lehet belépve",
+        *MAX_SESSIONS
+    ))?;
+    _ = user_ids.insert(uuid, Session::new(username));
+    drop(user_ids);
 
     #[cfg(feature = "server_state_save")]
     tokio::spawn(state_save::save_state());
 
-    SetHeader::new(format!("sid={uuid};HttpOnly;Secure;SameSite=Strict"))
-        .or_internal_server_error("valahogy érvénytelen munkamenet-azonosító sütit generáltunk...")
+    SetHeader::new(format!(
+        "sid={uuid};HttpOnly;Secure;SameSite=Strict;Max-Age={}",
+        SESSION_TTL.as_secs()
+    ))
+    .or_internal_server_error("valahogy érvénytelen munkamenet-azonosító sütit generáltunk...")
 }
 
 /// log out of the competition,
-/// `wipe_progress` if requested,
-/// otherwise preserve team progress for future relogins
+/// `wipe_progress` (and the team's password) if requested,
+/// otherwise preserve team progress for future relogins with the team's password
 ///
 /// returns empty, expired `sid` `SetCookie` header => browser deletes the valid one => user's now deauthed
 #[post("/api/logout", cookies: TypedHeader<Cookie>)]
 pub async fn logout(wipe_progress: Option<bool>) -> Result<SetHeader<SetCookie>, HttpError> {
     check_admin_pwd()?;
-    let uuid = extract_sid_cookie(cookies).await?;
+    let uuid = extract_sid_cookie(cookies.clone()).await?;
 
     if wipe_progress.is_some_and(|sure| sure) {
-        let username = USER_IDS
-            .read()
-            .await
-            .get(&uuid)
-            .or_not_found("nincs előrehaladás, így nem töröljük azt")?
-            .clone();
+        let (_, username) = authenticate(cookies).await?;
         info!("a {username:?} csapat előrelhaladása törlésre kerül");
         _ = TEAMS.write().await.remove(&username);
+        _ = TEAM_SECRETS.write().await.remove(&username);
+        // the team's gone, so are the sessions of its other members
+        USER_IDS
+            .write()
+            .await
+            .retain(|other_uuid, session| other_uuid == &uuid || session.username != username);
     }
 
     _ = USER_IDS
@@ -117,14 +154,77 @@ pub async fn logout(wipe_progress: Option<bool>) -> Result<SetHeader<SetCookie>,
         .or_internal_server_error("valahogy érvénytelen munkamenet-azonosító sütit generáltunk...")
 }
 
+/// set the password of the team, only if it has none yet,
+/// eg: it was loaded from state saved before passwords existed, see [`join`]
+#[post("/api/team_password", cookies: TypedHeader<Cookie>)]
+pub async fn set_team_password(mut password: String) -> Result<String, HttpError> {
+    check_admin_pwd()?;
+    let (_, username) = authenticate(cookies).await?;
+
+    let claimed = claim_team_password(&username, &password).await;
+    password.zeroize();
+    claimed?.or_forbidden("a csapatnak már van jelszava")?;
+    info!("a(z) {username:?} csapat beállította a jelszavát");
+
+    #[cfg(feature = "server_state_save")]
+    tokio::spawn(state_save::save_state());
+
+    Ok(String::from("beállítottuk a csapatjelszót"))
+}
+
+/// lists the sessions of the team
+#[get("/api/sessions", cookies: TypedHeader<Cookie>)]
+pub async fn sessions() -> Result<Vec<SessionInfo>, HttpError> {
+    check_admin_pwd()?;
+    let (uuid, username) = authenticate(cookies).await?;
+    let mut sessions = (USER_IDS.read().await.iter())
+        .filter(|(_, session)| session.username == username)
+        .map(|(other_uuid, session)| SessionInfo {
+            id: public_session_id(other_uuid),
+            current: other_uuid == &uuid,
+            created_at: session.created_at,
+            last_seen: session.last_seen,
+        })
+        .collect::<Vec<_>>();
+    sessions.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(sessions)
+}
+
+/// log out another session of the team by its public `session_id`, see [`sessions`]
+#[post("/api/revoke_session", cookies: TypedHeader<Cookie>)]
+pub async fn revoke_session(session_id: String) -> Result<String, HttpError> {
+    check_admin_pwd()?;
+    let (uuid, username) = authenticate(cookies).await?;
+    let mut user_ids = USER_IDS.write().await;
+    let revoked_uuid = *user_ids
+        .iter()
+        .find(|(other_uuid, session)| {
+            session.username == username && public_session_id(other_uuid) == session_id
+        })
+        .or_not_found("nincs ilyen munkamenete a csapatnak")?
+        .0;
+    (revoked_uuid != uuid).or_bad_request("a saját munkamenetedet kilépéssel zárhatod le")?;
+    _ = user_ids.remove(&revoked_uuid);
+    drop(user_ids);
+
+    #[cfg(feature = "server_state_save")]
+    tokio::spawn(state_save::save_state());
+
+    Ok(String::from("kiléptettük a munkamenetet"))
+}
+
+/// whether the admin password has already been set via [`set_passwd`]
+#[get("/api/admin_password_set")]
+pub async fn admin_pwd_set() -> Result<bool> {
+    Ok(check_admin_pwd().is_ok())
+}
+
 /// before this, no solution can be set, no state will be loaded
 /// NOTE: might take a while, as it hashes the `password` and loads the state
 /// NOTE: use https
-#[post("/api/set_admin_password")]
+#[post("/api/set_admin_password", client: ConnectInfo<SocketAddr>, headers: HeaderMap)]
 pub async fn set_passwd(init_password: String, mut password: String) -> Result<String, HttpError> {
-    INIT_PWD
-        .eq(&init_password)
-        .or_forbidden("érvénytelen beállítási jelszó")?;
+    verify_init_pwd(&init_password, client_ip(client.ip(), &headers)).await?;
 
     check_admin_pwd()
         .is_err()
@@ -152,36 +252,74 @@ pub async fn set_passwd(init_password: String, mut password: String) -> Result<S
     ))
 }
 
-/// set `puzzle_solutions` with `ADMIN_PASSWORD`
+/// log in as admin with `ADMIN_PASSWORD`, the admin endpoints accept the returned cookie,
+/// so it needn't be sent every time
+///
+/// NOTE: the session expires after `ADMIN_SESSION_TTL`
+#[post("/api/admin/login", client: ConnectInfo<SocketAddr>, headers: HeaderMap)]
+pub async fn admin_login(mut password: String) -> Result<SetHeader<SetCookie>, HttpError> {
+    verify_admin_pwd(&mut password, client_ip(client.ip(), &headers)).await?;
+
+    let uuid = Uuid::new_v4();
+    let ttl = *ADMIN_SESSION_TTL;
+    let expires_at = now().saturating_add(u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX));
+    _ = ADMIN_SESSIONS.write().await.insert(uuid, expires_at);
+    info!("új admin munkamenet, lejár: {:?} múlva", ttl);
+
+    SetHeader::new(format!(
+        "admin_sid={uuid};HttpOnly;Secure;SameSite=Strict;Max-Age={}",
+        ttl.as_secs()
+    ))
+    .or_internal_server_error(
+        "valahogy érvénytelen admin munkamenet-azonosító sütit generáltunk...",
+    )
+}
+
+/// when the admin session expires, if logged in
+#[get("/api/admin/auth_state", cookies: TypedHeader<Cookie>)]
+pub async fn admin_auth_state() -> Result<Timestamp, HttpError> {
+    let uuid = authenticate_admin(&cookies).await?;
+    ADMIN_SESSIONS
+        .read()
+        .await
+        .get(&uuid)
+        .copied()
+        .or_unauthorized("lejárt az admin munkamenet, jelentkezz be újra")
+}
+
+/// end the admin session
+///
+/// returns empty, expired `admin_sid` `SetCookie` header => browser deletes the valid one
+#[post("/api/admin/logout", cookies: TypedHeader<Cookie>)]
+pub async fn admin_logout() -> Result<SetHeader<SetCookie>, HttpError> {
+    let uuid = authenticate_admin(&cookies).await?;
+    _ = ADMIN_SESSIONS.write().await.remove(&uuid);
+
+    SetHeader::new("admin_sid=;Expires=Thu, 01 Jan 1970 00:00:00 GMT").or_internal_server_error(
+        "valahogy érvénytelen admin munkamenet-azonosító sütit generáltunk...",
+    )
+}
+
+/// set `puzzle_solutions` as admin, see [`admin_login`]
 ///
 /// NOTE: if any of the solutions is incorrect, none will be saved
-#[post("/api/set_solution")]
-pub async fn set_solution(
-    mut puzzle_solutions: PuzzleSolutions,
-    mut password: String,
-) -> Result<String, HttpError> {
-    // submitting as admin
-    let hashed_key = check_admin_pwd()?;
-    let pwd_matches = argon2::verify_raw(password.as_bytes(), &*SALT, hashed_key, &ARGON2CONF)
-        .inspect_err(|e| error!("nem sikerült azonosítani a jelszót: {e}"))
-        .or_internal_server_error("nem sikerült azonosítani a jelszót")?;
-    password.zeroize();
-    pwd_matches.or_unauthorized("érvénytelen jelszó")?;
+#[post("/api/set_solution", cookies: TypedHeader<Cookie>)]
+pub async fn set_solution(mut puzzle_solutions: PuzzleSolutions) -> Result<String, HttpError> {
+    authenticate_admin(&cookies).await?;
 
-    let puzzles_lock = PUZZLES.read().await;
-    puzzle_solutions
-        .keys()
-        .any(|new_k| !puzzles_lock.contains_key(new_k))
-        .or_forbidden("legalább egy feladat már be van állítva")?;
-    drop(puzzles_lock);
+    check_puzzles_unset(&*PUZZLES.read().await, &puzzle_solutions)?;
+    // NOTE: history is only kept for deleted puzzles, only another admin request could add some meanwhile
+    check_puzzles_historyless(&puzzle_solutions).await?;
 
     for puzzle in puzzle_solutions.values_mut() {
-        let solution_hash = hash_puzzle_solution(&puzzle.solution)?;
-        puzzle.solution.zeroize();
-        puzzle.solution = solution_hash;
+        hash_puzzle_solutions(puzzle, &gen_salt())?;
     }
 
-    PUZZLES.write().await.extend(puzzle_solutions);
+    let mut puzzles_lock = PUZZLES.write().await;
+    // someone might've set some of them while we were hashing
+    check_puzzles_unset(&puzzles_lock, &puzzle_solutions)?;
+    puzzles_lock.extend(puzzle_solutions);
+    drop(puzzles_lock);
 
     #[cfg(feature = "server_state_save")]
     tokio::spawn(state_save::save_state());
@@ -191,54 +329,289 @@ pub async fn set_solution(
     ))
 }
 
-/// submit a solution as a team
+/// base values of the existing puzzles as admin, without the worth decaying, see [`Puzzle`]
+#[get("/api/admin/puzzles", cookies: TypedHeader<Cookie>)]
+pub async fn admin_puzzles() -> Result<PuzzlesExisting, HttpError> {
+    authenticate_admin(&cookies).await?;
+    Ok((PUZZLES.read().await.iter())
+        .map(|(id, puzzle)| (id.clone(), puzzle.value))
+        .collect())
+}
+
+/// change the `value`, replace the `solution` and/or the `alternatives`, the whole `checker` or the `meta` of an existing puzzle as admin
+///
+/// NOTE: teams that already solved it keep it solved
+///
+/// the secret of [`AnswerChecker::PerTeam`] is kept if the new one is empty, unless `rotate_secret`,
+/// which changes every team's answer
+#[allow(clippy::too_many_arguments)] // NOTE: each one is a field of the request
+#[post("/api/admin/edit_puzzle", cookies: TypedHeader<Cookie>)]
+pub async fn edit_puzzle(
+    puzzle_id: PuzzleId,
+    value: Option<PuzzleValue>,
+    mut solution: Option<PuzzleSolution>,
+    mut alternatives: Option<Vec<PuzzleSolution>>,
+    mut checker: Option<AnswerChecker>,
+    meta: Option<PuzzleMeta>,
+    rotate_secret: Option<bool>,
+) -> Result<String, HttpError> {
+    authenticate_admin(&cookies).await?;
+
+    // the new hashes should share the salt and the normalization with the ones kept
+    let (salt, normalization, old_checker) = PUZZLES
+        .read()
+        .await
+        .get(&puzzle_id)
+        .map(|puzzle| {
+            let checker = puzzle.checker.clone();
+            (puzzle.solution.clone(), puzzle.normalization, checker)
+        })
+        .or_not_found("nincs ezzel az azonosítóval feladat")?;
+    let salt = encoded_salt(&salt).unwrap_or_else(|| gen_salt().to_vec());
+
+    let rotate_secret = rotate_secret.is_some_and(|sure| sure);
+    if rotate_secret && checker.is_none() {
+        checker = Some(old_checker.clone());
+    }
+    if let Some(AnswerChecker::PerTeam { secret, .. }) = &mut checker {
+        if rotate_secret {
+            secret.clear();
+        } else if let AnswerChecker::PerTeam {
+            secret: old_secret, ..
+        } = &old_checker
+            && secret.is_empty()
+        {
+            // NOTE: the teams' answers only change if asked to
+            secret.clone_from(old_secret);
+        }
+    } else {
+        (!rotate_secret).or_bad_request("csak csapatonkénti válaszú feladat titka cserélhető")?;
+    }
+
+    let is_hashed = checker.as_ref().unwrap_or(&old_checker) == &AnswerChecker::Hashed;
+    if let Some(checker) = &mut checker {
+        prepare_answer_checker(checker)?;
+    }
+    if !is_hashed {
+        (solution.is_none() && alternatives.is_none())
+            .or_bad_request("ennél az ellenőrzésnél nem adhatsz meg megoldásokat")?;
+    } else if old_checker != AnswerChecker::Hashed {
+        solution
+            .is_some()
+            .or_bad_request("ehhez az ellenőrzéshez meg kell adnod a megoldást")?;
+    }
+
+    let new_solutions = (solution.iter_mut()).chain(alternatives.iter_mut().flatten());
+    for new_solution in new_solutions {
+        (!new_solution.trim().is_empty()).or_bad_request("üres megoldást nem állíthatsz be")?;
+        let solution_hash = hash_puzzle_solution(new_solution, &normalization, &salt)?;
+        new_solution.zeroize();
+        *new_solution = solution_hash;
+    }
+
+    let mut puzzles = PUZZLES.write().await;
+    let puzzle = puzzles
+        .get_mut(&puzzle_id)
+        .or_not_found("nincs ezzel az azonosítóval feladat")?;
+    if let Some(value) = value {
+        puzzle.value = value;
+    }
+    if let Some(meta) = meta {
+        puzzle.meta = meta;
+    }
+    if let Some(solution_hash) = solution {
+        puzzle.solution = solution_hash;
+    }
+    if let Some(alternative_hashes) = alternatives {
+        puzzle.alternatives = alternative_hashes;
+    }
+    if let Some(checker) = checker {
+        puzzle.checker = checker;
+    }
+    if !is_hashed {
+        // the solutions of the old checker won't ever be used again
+        puzzle.solution.clear();
+        puzzle.alternatives.clear();
+    }
+    drop(puzzles);
+    info!("a {puzzle_id:?} feladat módosult");
+
+    #[cfg(feature = "server_state_save")]
+    tokio::spawn(state_save::save_state());
+
+    Ok(String::from("sikeresen módosítottuk a feladatot"))
+}
+
+/// delete an existing puzzle as admin, the teams' progress on it is handled by `policy`
+#[post("/api/admin/delete_puzzle", cookies: TypedHeader<Cookie>)]
+pub async fn delete_puzzle(puzzle_id: PuzzleId, policy: DeletePolicy) -> Result<String, HttpError> {
+    authenticate_admin(&cookies).await?;
+
+    _ = PUZZLES
+        .write()
+        .await
+        .remove(&puzzle_id)
+        .or_not_found("nincs ezzel az azonosítóval feladat")?;
+    if policy == DeletePolicy::Purge {
+        for team in TEAMS.write().await.values_mut() {
+            _ = team.solved.remove(&puzzle_id);
+            _ = team.wrong_attempts.remove(&puzzle_id);
+        }
+    }
+    info!("a {puzzle_id:?} feladat törölve");
+
+    #[cfg(feature = "server_state_save")]
+    tokio::spawn(state_save::save_state());
+
+    Ok(String::from("sikeresen töröltük a feladatot"))
+}
+
+/// set the password of an existing `team` as admin, replacing the one it had if any
+#[post("/api/admin/team_password", cookies: TypedHeader<Cookie>)]
+pub async fn admin_set_team_password(
+    team: String,
+    mut password: String,
+) -> Result<String, HttpError> {
+    authenticate_admin(&cookies).await?;
+
+    let is_existing = TEAMS.read().await.contains_key(&team);
+    let reset = if is_existing {
+        reset_team_password(&team, &password).await
+    } else {
+        HttpError::not_found("nincs ilyen nevű csapat")
+    };
+    password.zeroize();
+    reset?;
+    info!("a(z) {team:?} csapat jelszavát beállította az admin");
+
+    #[cfg(feature = "server_state_save")]
+    tokio::spawn(state_save::save_state());
+
+    Ok(String::from("beállítottuk a csapatjelszót"))
+}
+
+/// every submission attempt so far as admin, oldest first, see [`admin_login`]
+#[get("/api/submissions", cookies: TypedHeader<Cookie>)]
+pub async fn submissions() -> Result<SubmissionLog, HttpError> {
+    authenticate_admin(&cookies).await?;
+    Ok(SUBMISSIONS.read().await.clone())
+}
+
+/// the answers of every team that joined, and of the `teams` listed, eg: to hand them out before joining,
+/// to each puzzle with per-team answers as admin, see [`AnswerChecker::PerTeam`]
+#[post("/api/admin/team_answers", cookies: TypedHeader<Cookie>)]
+pub async fn team_answers(mut teams: Vec<String>) -> Result<TeamAnswers, HttpError> {
+    authenticate_admin(&cookies).await?;
+
+    teams.extend(TEAMS.read().await.keys().cloned());
+    teams.retain(|team| !team.trim().is_empty());
+    teams.sort();
+    teams.dedup();
+
+    let mut answers = TeamAnswers::new();
+    for (puzzle_id, puzzle) in PUZZLES.read().await.iter() {
+        let AnswerChecker::PerTeam { secret, template } = &puzzle.checker else {
+            continue;
+        };
+        let puzzle_answers = (teams.iter())
+            .map(|team| Ok((team.clone(), team_answer(secret, template, team)?)))
+            .collect::<Result<_, HttpError>>()?;
+        _ = answers.insert(puzzle_id.clone(), puzzle_answers);
+    }
+    Ok(answers)
+}
+
+/// submit a solution as a team, the attempt is logged whether it's right or not, or even rejected
 ///
 /// We'll use the `TypedHeader` extractor on the server to get the cookie from the request.
-#[post("/api/submit", cookies: TypedHeader<Cookie>)]
+#[post("/api/submit", cookies: TypedHeader<Cookie>, client: ConnectInfo<SocketAddr>, headers: HeaderMap)]
 pub async fn submit_solution(
     puzzle_id: PuzzleId,
     mut solution: PuzzleSolution,
 ) -> Result<String, HttpError> {
     check_admin_pwd()?;
-    let uuid = extract_sid_cookie(cookies).await?;
-    let username = USER_IDS
-        .read()
-        .await
-        .get(&uuid)
-        .or_not_found("nincs ezzel az azonosítóval csapat")?
-        .clone(); // PERF: rather clone than lock
+    let (uuid, username) = authenticate(cookies).await?;
+
+    let session = public_session_id(&uuid);
+    let ip = client_ip(client.ip(), &headers);
+    let submission = |time, correct, rejected| Submission {
+        team: username.clone(),
+        session: session.clone(),
+        puzzle_id: puzzle_id.clone(),
+        time,
+        correct,
+        ip,
+        shared_from: None,
+        rejected,
+    };
+    // NOTE: rejected attempts are logged too, only what was sent isn't known about them
+    let reject = async |rejected: Option<Rejection>, e: HttpError| {
+        if let Some(rejected) = rejected {
+            SUBMISSIONS
+                .write()
+                .await
+                .push(submission(now(), false, Some(rejected)));
+            #[cfg(feature = "server_state_save")]
+            tokio::spawn(state_save::save_state());
+        }
+        Err(e)
+    };
 
-    let is_solution_valid = {
-        let puzzle = PUZZLES
-            .read()
-            .await
-            .get(&puzzle_id)
-            .or_not_found("nincs ezzel az azonosítóval feladat")?
-            .clone(); // PERF: verification is relatively slow, clone instead of locking
-        (!TEAMS
-            .read()
-            .await
-            .get(&username)
-            .or_internal_server_error("nincs ehhez a csapatnévhez előrehaladás rendelve")?
-            .contains(&puzzle_id)) // not contains
-        .or_forbidden("ezt a feladatot már megoldottad")?;
-
-        argon2::verify_encoded(&puzzle.solution, solution.as_bytes())
-            .inspect_err(|e| error!("nem sikerült ellenőrizni a feladatmegoldást: {e}"))
-            .or_internal_server_error("nem sikerült ellenőrizni a feladatmegoldást")?
+    let puzzle = PUZZLES.read().await.get(&puzzle_id).cloned(); // PERF: verification is relatively slow, clone instead of locking
+    let Some(puzzle) = puzzle else {
+        let e = HttpError::new(StatusCode::NOT_FOUND, "nincs ezzel az azonosítóval feladat");
+        return reject(Some(Rejection::UnknownPuzzle), e).await;
+    };
+    let rules = puzzle.attempt_rules.unwrap_or(*ATTEMPT_RULES);
+    let reservation = match reserve_attempt(&username, &puzzle_id, &rules).await {
+        Ok(reservation) => reservation,
+        Err((rejected, e)) => return reject(rejected, e).await,
     };
+
+    let mut answer = puzzle.normalization.apply(&solution);
     solution.zeroize();
-    is_solution_valid.or_forbidden("érvénytelen megoldás ehhez a feladathoz")?;
+    let checked = match verify_puzzle_solution(&puzzle, &answer, &username).await {
+        Ok(false) => (answer_owner(&puzzle, &answer, &username).await).map(|owner| (false, owner)),
+        checked => checked.map(|is_valid| (is_valid, None)),
+    };
+    answer.zeroize();
+    let time = now();
+    let (is_solution_valid, shared_from) = match checked {
+        Ok(checked) => checked,
+        Err(e) => {
+            // not the team's fault, don't count it
+            settle_attempt(reservation, &puzzle, None, time).await?;
+            return reject(Some(Rejection::CheckFailed), e).await;
+        }
+    };
 
-    TEAMS
-        .write()
-        .await
-        .get_mut(&username)
-        .or_internal_server_error("nincs ehhez a csapatnévhez előrehaladás rendelve")?
-        .insert(puzzle_id);
+    SUBMISSIONS.write().await.push(Submission {
+        shared_from: shared_from.clone(),
+        ..submission(time, is_solution_valid, None)
+    });
+    if let Some(owner) = shared_from {
+        // NOTE: the team isn't told, it's just a wrong answer for them
+        warn!(
+            "a(z) {username:?} csapat a(z) {owner:?} csapat válaszát küldte be a(z) {puzzle_id:?} feladatra"
+        );
+    }
+
+    let bonus = settle_attempt(reservation, &puzzle, Some(is_solution_valid), time).await?;
 
     #[cfg(feature = "server_state_save")]
     tokio::spawn(state_save::save_state());
 
+    if !is_solution_valid && rules.penalty > 0 {
+        return HttpError::forbidden(format!(
+            "érvénytelen megoldás ehhez a feladathoz, -{} pont",
+            rules.penalty
+        ));
+    }
+    is_solution_valid.or_forbidden("érvénytelen megoldás ehhez a feladathoz")?;
+    if bonus > 0 {
+        return Ok(format!(
+            "hurrá, sikeresen elmentettük a megoldásod, és az elsők közt voltatok: +{bonus} bónuszpont!"
+        ));
+    }
     Ok(String::from("hurrá, sikeresen elmentettük a megoldásod!"))
 }
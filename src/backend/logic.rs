@@ -1,22 +1,74 @@
 use super::models::*;
-use dioxus::fullstack::{Cookie, TypedHeader};
+use base64::Engine;
+use dioxus::fullstack::{Cookie, HeaderMap, TypedHeader, serde};
 use dioxus::prelude::*;
+use hmac::{Hmac, KeyInit, Mac};
 use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use std::collections::{HashMap, hash_map::Entry};
+use std::net::IpAddr;
+use std::num::NonZeroU32;
 use std::sync::{LazyLock, OnceLock};
-use std::{collections::HashMap, env};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, fmt::Display, str::FromStr};
+use subtle::ConstantTimeEq;
 use tokio::sync::RwLock;
 use uuid::Uuid;
+use zeroize::Zeroize;
 
-/// who's joined -> their name
-type Teams = HashMap<Uuid, String>;
-pub(super) static USER_IDS: LazyLock<RwLock<Teams>> = LazyLock::new(|| RwLock::new(Teams::new()));
+/// a team member's login
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub(super) struct Session {
+    pub(super) username: String,
+    pub(super) created_at: Timestamp,
+    pub(super) last_seen: Timestamp,
+}
+
+impl Session {
+    pub(super) fn new(username: String) -> Self {
+        let now = now();
+        Self {
+            username,
+            created_at: now,
+            last_seen: now,
+        }
+    }
+
+    /// whether it's older than `SESSION_TTL` or unused for longer than `SESSION_IDLE_TTL`
+    fn is_expired(&self, now: Timestamp) -> bool {
+        let millis = |dur: Duration| u64::try_from(dur.as_millis()).unwrap_or(u64::MAX);
+        now.saturating_sub(self.created_at) > millis(*SESSION_TTL)
+            || now.saturating_sub(self.last_seen) > millis(*SESSION_IDLE_TTL)
+    }
+}
+
+/// who's joined -> their session
+type Sessions = HashMap<Uuid, Session>;
+pub(super) static USER_IDS: LazyLock<RwLock<Sessions>> =
+    LazyLock::new(|| RwLock::new(Sessions::new()));
 
 pub(super) static PUZZLES: LazyLock<RwLock<PuzzleSolutions>> =
     LazyLock::new(|| RwLock::new(PuzzleSolutions::new()));
 
+/// logged in admins -> when their session expires
+///
+/// NOTE: not saved, the master password has to be set again after a restart anyway
+type AdminSessions = HashMap<Uuid, Timestamp>;
+pub(super) static ADMIN_SESSIONS: LazyLock<RwLock<AdminSessions>> =
+    LazyLock::new(|| RwLock::new(AdminSessions::new()));
+
 pub(super) static TEAMS: LazyLock<RwLock<TeamsState>> =
     LazyLock::new(|| RwLock::new(TeamsState::new()));
 
+pub(super) static SUBMISSIONS: LazyLock<RwLock<SubmissionLog>> =
+    LazyLock::new(|| RwLock::new(SubmissionLog::new()));
+
+/// team name -> argon2-encoded hash of its password, set on first join
+type TeamSecrets = HashMap<String, String>;
+pub(super) static TEAM_SECRETS: LazyLock<RwLock<TeamSecrets>> =
+    LazyLock::new(|| RwLock::new(TeamSecrets::new()));
+
 // SECURITY: it's fine like this, right?
 pub(super) static SALT: LazyLock<[u8; 32]> = LazyLock::new(gen_salt);
 
@@ -26,6 +78,70 @@ pub(super) static HASHED_PWD: OnceLock<Vec<u8>> = OnceLock::new();
 pub static INIT_PWD: LazyLock<String> = LazyLock::new(|| Uuid::new_v4().to_string());
 pub(super) static EVENT_TITLE: LazyLock<Result<String, env::VarError>> =
     LazyLock::new(|| env::var("APOLLO_EVENT_TITLE"));
+/// how many sessions a team may have at once
+pub(super) static MAX_SESSIONS: LazyLock<usize> =
+    LazyLock::new(|| env_or("APOLLO_MAX_SESSIONS", 4));
+
+/// sessions expire this long after joining
+pub(super) static SESSION_TTL: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_or("APOLLO_SESSION_TTL_SECS", 12 * 60 * 60)));
+/// admin sessions expire this long after logging in
+pub(super) static ADMIN_SESSION_TTL: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_or("APOLLO_ADMIN_SESSION_TTL_SECS", 60 * 60)));
+/// sessions expire after being unused for this long
+static SESSION_IDLE_TTL: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_or("APOLLO_SESSION_IDLE_TTL_SECS", 2 * 60 * 60)));
+
+/// what wrong answers cost, unless a puzzle has its own `attempt_rules`
+pub(super) static ATTEMPT_RULES: LazyLock<AttemptRules> = LazyLock::new(|| AttemptRules {
+    penalty: env_or("APOLLO_WRONG_ATTEMPT_PENALTY", 0),
+    cooldown_after: env_or("APOLLO_COOLDOWN_AFTER", 0),
+    cooldown_secs: env_or("APOLLO_COOLDOWN_SECS", 60),
+    max_attempts: env_or("APOLLO_MAX_ATTEMPTS", 0),
+});
+
+/// addresses of the reverse proxies whose `X-Forwarded-For` header we trust
+static TRUSTED_PROXIES: LazyLock<Vec<IpAddr>> = LazyLock::new(|| {
+    (env_or("APOLLO_TRUSTED_PROXIES", String::new()).split(','))
+        .map(str::trim)
+        .filter(|proxy| !proxy.is_empty())
+        .filter_map(|proxy| {
+            proxy
+                .parse()
+                .inspect_err(|e| warn!("érvénytelen megbízható proxy cím ({proxy:?}): {e}"))
+                .ok()
+        })
+        .collect()
+});
+
+/// parse env var `key`, or use `default` if it's unset or invalid
+pub(super) fn env_or<T: FromStr<Err: Display> + Display>(key: &str, default: T) -> T {
+    let Ok(raw) = env::var(key) else {
+        return default;
+    };
+    raw.parse()
+        .inspect_err(|e| {
+            warn!("érvénytelen a {key} környezeti változó ({raw:?}): {e}, alapértelmezettet használunk ({default})")
+        })
+        .unwrap_or(default)
+}
+
+/// address of the client: `peer`, unless it's a trusted proxy,
+/// then the closest address in `X-Forwarded-For` that isn't one
+pub(super) fn client_ip(peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+    if !TRUSTED_PROXIES.contains(&peer) {
+        return peer;
+    }
+    let forwarded_for = (headers.get_all("x-forwarded-for").iter())
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect::<Vec<_>>();
+    // the ones at the end were added by our proxies, the rest might've been made up by anyone
+    (forwarded_for.into_iter().rev())
+        .map_while(|addr| addr.trim().parse::<IpAddr>().ok())
+        .find(|addr| !TRUSTED_PROXIES.contains(addr))
+        .unwrap_or(peer)
+}
 
 /// check whether admin password was set
 pub(super) fn check_admin_pwd() -> Result<&'static Vec<u8>, HttpError> {
@@ -34,28 +150,554 @@ pub(super) fn check_admin_pwd() -> Result<&'static Vec<u8>, HttpError> {
         .or_forbidden("még nincs beállítva mesterjelszó")
 }
 
-fn gen_salt() -> [u8; 32] {
+/// failed admin authentications from an address
+struct AdminFailures {
+    count: u32,
+    last: Instant,
+}
+static ADMIN_FAILURES: LazyLock<RwLock<HashMap<IpAddr, AdminFailures>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// an address is locked out after this many failed admin authentications in a row
+///
+/// NOTE: 0 is rejected, it would lock everyone out before their first try
+static ADMIN_LOCKOUT_AFTER: LazyLock<u32> = LazyLock::new(|| {
+    let default = NonZeroU32::new(5).unwrap_or(NonZeroU32::MIN);
+    env_or("APOLLO_ADMIN_LOCKOUT_AFTER", default).get()
+});
+/// for this long, failures are forgotten after this long too
+static ADMIN_LOCKOUT: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_or("APOLLO_ADMIN_LOCKOUT_SECS", 15 * 60)));
+
+impl AdminFailures {
+    /// how long the address has to wait after its last failure:
+    /// 1s, 2s, 4s, ... then `ADMIN_LOCKOUT` after `ADMIN_LOCKOUT_AFTER` failures
+    fn backoff(&self) -> Duration {
+        if self.count >= *ADMIN_LOCKOUT_AFTER {
+            return *ADMIN_LOCKOUT;
+        }
+        let exp = self.count.saturating_sub(1).min(16);
+        Duration::from_secs(1 << exp).min(*ADMIN_LOCKOUT)
+    }
+}
+
+/// reject `ip` while it's backing off after failed admin authentications,
+/// otherwise count this attempt as failed before it's verified, see [`record_admin_auth`]
+///
+/// NOTE: counted up front, so parallel attempts can't all get through before the first one fails
+async fn reserve_admin_auth(ip: IpAddr) -> Result<(), HttpError> {
+    let mut failures = ADMIN_FAILURES.write().await;
+    // start over if the last failure was long ago
+    failures.retain(|_, failure| failure.last.elapsed() < *ADMIN_LOCKOUT);
+    let failure = failures.entry(ip).or_insert(AdminFailures {
+        count: 0,
+        last: Instant::now(),
+    });
+    let wait = match failure.count {
+        0 => Duration::ZERO,
+        _ => failure.backoff().saturating_sub(failure.last.elapsed()),
+    };
+    if !wait.is_zero() {
+        warn!("sikertelen admin azonosítások után várnia kell: {ip}, még {wait:?}");
+        return HttpError::too_many_requests(format!(
+            "túl sok sikertelen próbálkozás, próbáld újra {} másodperc múlva",
+            wait.as_millis().div_ceil(1000)
+        ));
+    }
+    failure.count += 1;
+    failure.last = Instant::now();
+    Ok(())
+}
+
+/// log a failed admin authentication of `ip` at `what`, already counted by [`reserve_admin_auth`],
+/// forget them on success
+async fn record_admin_auth(ip: IpAddr, what: &str, success: bool) {
+    let mut failures = ADMIN_FAILURES.write().await;
+    if success {
+        _ = failures.remove(&ip);
+        return;
+    }
+    let count = failures.get(&ip).map_or(0, |failure| failure.count);
+    warn!("sikertelen admin azonosítás ({what}): {ip}, egymás után {count}. alkalommal");
+    if count == *ADMIN_LOCKOUT_AFTER {
+        warn!("{ip} kizárva {:?} időre", *ADMIN_LOCKOUT);
+    }
+}
+
+/// check `password` against the admin password, zeroizes it
+///
+/// `ip` has to back off after failing, see [`AdminFailures::backoff`]
+pub(super) async fn verify_admin_pwd(password: &mut String, ip: IpAddr) -> Result<(), HttpError> {
+    let hashed_key = check_admin_pwd()?;
+    if let Err(e) = reserve_admin_auth(ip).await {
+        password.zeroize();
+        return Err(e);
+    }
+    let pwd_matches = argon2::verify_raw(password.as_bytes(), &*SALT, hashed_key, &ARGON2CONF)
+        .inspect_err(|e| error!("nem sikerült azonosítani a jelszót: {e}"))
+        .or_internal_server_error("nem sikerült azonosítani a jelszót");
+    password.zeroize();
+    let pwd_matches = pwd_matches?;
+    record_admin_auth(ip, "mesterjelszó", pwd_matches).await;
+    pwd_matches.or_unauthorized("érvénytelen jelszó")
+}
+
+/// check `init_password` against [`INIT_PWD`] in constant time
+///
+/// `ip` has to back off after failing, see [`AdminFailures::backoff`]
+pub(super) async fn verify_init_pwd(init_password: &str, ip: IpAddr) -> Result<(), HttpError> {
+    reserve_admin_auth(ip).await?;
+    let matches = bool::from(init_password.as_bytes().ct_eq(INIT_PWD.as_bytes()));
+    record_admin_auth(ip, "beállítási jelszó", matches).await;
+    matches.or_forbidden("érvénytelen beállítási jelszó")
+}
+
+pub(super) fn gen_salt() -> [u8; 32] {
     let mut salt = [0u8; 32];
     OsRng.fill_bytes(&mut salt);
     salt
 }
 
-pub(super) fn hash_puzzle_solution(raw_solution: &str) -> Result<PuzzleSolutionHash, HttpError> {
-    argon2::hash_encoded(raw_solution.as_bytes(), &gen_salt(), &ARGON2CONF)
+/// hash `raw_solution` after tidying it up with `normalization`, see [`Normalization::apply`]
+pub(super) fn hash_puzzle_solution(
+    raw_solution: &str,
+    normalization: &Normalization,
+    salt: &[u8],
+) -> Result<PuzzleSolutionHash, HttpError> {
+    let mut normalized = normalization.apply(raw_solution);
+    let solution_hash = argon2::hash_encoded(normalized.as_bytes(), salt, &ARGON2CONF)
         .inspect_err(|e| error!("nem sikerült hasítani egy feladatmegoldást: {e}"))
-        .or_internal_server_error("nem sikerült hasítani egy feladatmegoldást")
+        .or_internal_server_error("nem sikerült hasítani egy feladatmegoldást");
+    normalized.zeroize();
+    solution_hash
 }
 
-/// get a clone of state: `TEAMS` and `PUZZLES`
-pub(super) async fn get_game_state() -> (TeamsState, PuzzlesExisting) {
-    let existing_puzzles = PUZZLES
-        .read()
+/// hash the raw `solution` and `alternatives` of `puzzle` in place with the same `salt`, zeroizes them
+///
+/// bad request if its `checker` is invalid or doesn't use them, but they're set anyway
+pub(super) fn hash_puzzle_solutions(puzzle: &mut Puzzle, salt: &[u8]) -> Result<(), HttpError> {
+    prepare_answer_checker(&mut puzzle.checker)?;
+    if puzzle.checker != AnswerChecker::Hashed {
+        (puzzle.solution.is_empty() && puzzle.alternatives.is_empty())
+            .or_bad_request("ennél az ellenőrzésnél nem adhatsz meg megoldásokat")?;
+        return Ok(());
+    }
+    for solution in std::iter::once(&mut puzzle.solution).chain(&mut puzzle.alternatives) {
+        let solution_hash = hash_puzzle_solution(solution, &puzzle.normalization, salt)?;
+        solution.zeroize();
+        *solution = solution_hash;
+    }
+    Ok(())
+}
+
+/// salt of an argon2-encoded hash, eg: `$argon2i$v=19$m=4096,t=3,p=1$<salt>$<hash>`
+pub(super) fn encoded_salt(encoded: &str) -> Option<Vec<u8>> {
+    let salt = encoded.split('$').nth(4)?;
+    base64::engine::general_purpose::STANDARD_NO_PAD
+        .decode(salt)
+        .ok()
+}
+
+/// bad request if `checker` could never accept anything, generates the secret of per-team answers if it's not set
+pub(super) fn prepare_answer_checker(checker: &mut AnswerChecker) -> Result<(), HttpError> {
+    match checker {
+        AnswerChecker::Hashed => Ok(()),
+        AnswerChecker::NumericRange { min, max } => {
+            (min.is_finite() && max.is_finite() && min <= max)
+                .or_bad_request("érvénytelen számtartomány")
+        }
+        AnswerChecker::Regex { pattern } => checker_regex(pattern)
+            .map(drop)
+            .or_bad_request("érvénytelen minta"),
+        AnswerChecker::External { program } => super::checker::check_program(program),
+        AnswerChecker::PerTeam { secret, template } => {
+            (template.contains(HMAC_PLACEHOLDER)).or_bad_request(format!(
+                "a sablonban szerepelnie kell ennek: {HMAC_PLACEHOLDER}"
+            ))?;
+            if secret.is_empty() {
+                *secret = base64::engine::general_purpose::STANDARD_NO_PAD.encode(gen_salt());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// the answer `team` is expected to submit to a puzzle with [`AnswerChecker::PerTeam`], not normalized yet
+pub(super) fn team_answer(secret: &str, template: &str, team: &str) -> Result<String, HttpError> {
+    /// 64 bits, unguessable but still easy to type
+    const HMAC_HEX_LEN: usize = 16;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .or_internal_server_error("nem sikerült előállítani a csapat válaszát")?;
+    mac.update(team.as_bytes());
+    let hmac = (mac.finalize().into_bytes().iter())
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    Ok(template.replace(HMAC_PLACEHOLDER, &hmac[..HMAC_HEX_LEN]))
+}
+
+/// the other team whose own answer to `puzzle` `answer` is, if it has [`AnswerChecker::PerTeam`]
+///
+/// NOTE: `answer` should already be normalized, see [`Normalization::apply`]
+pub(super) async fn answer_owner(
+    puzzle: &Puzzle,
+    answer: &str,
+    team: &str,
+) -> Result<Option<String>, HttpError> {
+    let AnswerChecker::PerTeam { secret, template } = &puzzle.checker else {
+        return Ok(None);
+    };
+    let teams = TEAMS.read().await.keys().cloned().collect::<Vec<_>>();
+    for other in teams.into_iter().filter(|other| other != team) {
+        let expected = puzzle
+            .normalization
+            .apply(&team_answer(secret, template, &other)?);
+        if bool::from(expected.as_bytes().ct_eq(answer.as_bytes())) {
+            return Ok(Some(other));
+        }
+    }
+    Ok(None)
+}
+
+/// `pattern` compiled to match whole answers only
+fn checker_regex(pattern: &str) -> Result<regex::Regex, regex::Error> {
+    /// SECURITY: the regex crate runs in linear time, but huge patterns could still eat up memory
+    const SIZE_LIMIT: usize = 1 << 20;
+
+    // NOTE: a pattern valid on its own can't close the group it's wrapped in
+    regex::RegexBuilder::new(pattern)
+        .size_limit(SIZE_LIMIT)
+        .build()?;
+    regex::RegexBuilder::new(&format!("^(?:{pattern})$"))
+        .size_limit(SIZE_LIMIT)
+        .build()
+}
+
+/// check `answer` of `team` with the [`AnswerChecker`] of `puzzle`
+///
+/// NOTE: `answer` should already be normalized, see [`Normalization::apply`]
+pub(super) async fn verify_puzzle_solution(
+    puzzle: &Puzzle,
+    answer: &str,
+    team: &str,
+) -> Result<bool, HttpError> {
+    match &puzzle.checker {
+        AnswerChecker::Hashed => verify_hashed_solution(puzzle, answer),
+        AnswerChecker::NumericRange { min, max } => {
+            let number = answer.trim().replace(',', ".").parse::<f64>();
+            Ok(number.is_ok_and(|number| (min..=max).contains(&&number)))
+        }
+        // PERF: compiled for every answer, but it's way faster than hashing anyway
+        AnswerChecker::Regex { pattern } => Ok(checker_regex(pattern)
+            .inspect_err(|e| error!("nem sikerült lefordítani a feladat mintáját: {e}"))
+            .or_internal_server_error("nem sikerült ellenőrizni a feladatmegoldást")?
+            .is_match(answer)),
+        AnswerChecker::External { program } => super::checker::run(program, answer).await,
+        AnswerChecker::PerTeam { secret, template } => {
+            let expected = puzzle
+                .normalization
+                .apply(&team_answer(secret, template, team)?);
+            Ok(bool::from(expected.as_bytes().ct_eq(answer.as_bytes())))
+        }
+    }
+}
+
+/// check `answer` against every accepted solution of `puzzle`
+///
+/// PERF: it's hashed only once, with the salt they share, see [`hash_puzzle_solutions`]
+fn verify_hashed_solution(puzzle: &Puzzle, answer: &str) -> Result<bool, HttpError> {
+    let accepted = std::iter::once(&puzzle.solution).chain(&puzzle.alternatives);
+    let answer_hash = match encoded_salt(&puzzle.solution) {
+        Some(salt) => hash_puzzle_solution(answer, &Normalization::default(), &salt)?,
+        None => String::new(),
+    };
+    let params = |encoded: &str| {
+        encoded
+            .rsplit_once('$')
+            .map(|(params, _)| params.to_owned())
+    };
+
+    let mut is_valid = false;
+    for solution_hash in accepted {
+        is_valid |= if params(solution_hash) == params(&answer_hash) {
+            bool::from(solution_hash.as_bytes().ct_eq(answer_hash.as_bytes()))
+        } else {
+            // hashed with another salt or config somehow, fall back to the slow way
+            argon2::verify_encoded(solution_hash, answer.as_bytes())
+                .inspect_err(|e| error!("nem sikerült ellenőrizni a feladatmegoldást: {e}"))
+                .or_internal_server_error("nem sikerült ellenőrizni a feladatmegoldást")?
+        };
+    }
+    Ok(is_valid)
+}
+
+fn hash_team_password(raw_password: &str) -> Result<String, HttpError> {
+    argon2::hash_encoded(raw_password.as_bytes(), &gen_salt(), &ARGON2CONF)
+        .inspect_err(|e| error!("nem sikerült hasítani egy csapatjelszót: {e}"))
+        .or_internal_server_error("nem sikerült hasítani egy csapatjelszót")
+}
+
+/// check `password` against the one `username` has set,
+/// or set it if this is the first join of the team
+///
+/// NOTE: teams loaded from state saved before passwords existed can't be joined until the admin
+/// or one of their sessions sets their password, see [`claim_team_password`]
+pub(super) async fn check_team_password(username: &str, password: &str) -> Result<(), HttpError> {
+    (!password.is_empty()).or_bad_request("a csapatjelszó nem lehet üres")?;
+
+    let secret_hash = TEAM_SECRETS.read().await.get(username).cloned(); // PERF: don't lock while verifying
+    if let Some(secret_hash) = secret_hash {
+        return argon2::verify_encoded(&secret_hash, password.as_bytes())
+            .inspect_err(|e| error!("nem sikerült ellenőrizni a csapatjelszót: {e}"))
+            .or_internal_server_error("nem sikerült ellenőrizni a csapatjelszót")?
+            .or_unauthorized("érvénytelen csapatjelszó");
+    }
+    (!TEAMS.read().await.contains_key(username)).or_forbidden(
+        "ennek a csapatnak még nincs jelszava, a szervezők vagy egy belépett tagja állíthatja be",
+    )?;
+
+    // someone else has claimed this name while we were hashing
+    (claim_team_password(username, password).await?)
+        .or_forbidden("ezt a csapatnevet épp most foglalták le")
+}
+
+/// set `password` for `username` unless it already has one, whether it was set
+pub(super) async fn claim_team_password(username: &str, password: &str) -> Result<bool, HttpError> {
+    (!password.is_empty()).or_bad_request("a csapatjelszó nem lehet üres")?;
+
+    let secret_hash = hash_team_password(password)?;
+    match TEAM_SECRETS.write().await.entry(username.to_string()) {
+        Entry::Vacant(entry) => _ = entry.insert(secret_hash),
+        Entry::Occupied(_) => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// set `password` for `username`, even if it already has one, eg: when they forgot it
+pub(super) async fn reset_team_password(username: &str, password: &str) -> Result<(), HttpError> {
+    (!password.is_empty()).or_bad_request("a csapatjelszó nem lehet üres")?;
+
+    let secret_hash = hash_team_password(password)?;
+    _ = TEAM_SECRETS
+        .write()
         .await
-        .clone()
-        .into_iter()
-        .map(|(id, pzl)| (id, pzl.value))
+        .insert(username.to_string(), secret_hash);
+    Ok(())
+}
+
+/// forbidden if any of `new_puzzles` is already set in `puzzles`, listing all the clashing ids
+pub(super) fn check_puzzles_unset(
+    puzzles: &PuzzleSolutions,
+    new_puzzles: &PuzzleSolutions,
+) -> Result<(), HttpError> {
+    let mut clashing_ids = new_puzzles
+        .keys()
+        .filter(|id| puzzles.contains_key(*id))
+        .cloned()
+        .collect::<Vec<_>>();
+    clashing_ids.sort();
+    clashing_ids.is_empty().or_forbidden(format!(
+        "legalább egy feladat már be van állítva: {}",
+        clashing_ids.join(", ")
+    ))
+}
+
+/// forbidden if any of `new_puzzles` was deleted with its history kept, listing all of them,
+/// as the history would count again, see [`DeletePolicy::KeepHistory`]
+pub(super) async fn check_puzzles_historyless(
+    new_puzzles: &PuzzleSolutions,
+) -> Result<(), HttpError> {
+    let teams = TEAMS.read().await;
+    let mut clashing_ids = new_puzzles
+        .keys()
+        .filter(|id| {
+            (teams.values())
+                .any(|team| team.solved.contains_key(*id) || team.wrong_attempts.contains_key(*id))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    clashing_ids.sort();
+    clashing_ids.is_empty().or_forbidden(format!(
+        "legalább egy feladat egy törölt, de megőrzött előzményű feladat azonosítóját kapná: {}",
+        clashing_ids.join(", ")
+    ))
+}
+
+/// count an attempt of `username` at `puzzle_id` as wrong before it's verified,
+/// so that parallel attempts of the team can't get around the `rules`,
+/// see [`settle_attempt`]
+///
+/// the [`Rejection`] is `None` if it's not the team's doing
+pub(super) async fn reserve_attempt(
+    username: &str,
+    puzzle_id: &PuzzleId,
+    rules: &AttemptRules,
+) -> Result<Reservation, (Option<Rejection>, HttpError)> {
+    let mut teams = TEAMS.write().await;
+    let team = teams
+        .get_mut(username)
+        .or_internal_server_error("nincs ehhez a csapatnévhez előrehaladás rendelve")
+        .map_err(|e| (None, e))?;
+    if team.solved.contains_key(puzzle_id) {
+        let e = HttpError::new(StatusCode::FORBIDDEN, "ezt a feladatot már megoldottad");
+        return Err((Some(Rejection::AlreadySolved), e));
+    }
+
+    let wrong = team.wrong_attempts.entry(puzzle_id.clone()).or_default();
+    if rules.max_attempts > 0 && wrong.count >= rules.max_attempts {
+        let msg = format!(
+            "elfogyott mind a(z) {} próbálkozásotok ennél a feladatnál",
+            rules.max_attempts
+        );
+        return Err((
+            Some(Rejection::Locked),
+            HttpError::new(StatusCode::FORBIDDEN, msg),
+        ));
+    }
+    let now = now();
+    if rules.cooldown_after > 0 && wrong.count > 0 && wrong.count % rules.cooldown_after == 0 {
+        let until = (wrong.last).saturating_add(rules.cooldown_secs.saturating_mul(1000));
+        if now < until {
+            let msg = format!(
+                "túl sok hibás próbálkozás, még {} másodpercig várnotok kell",
+                (until - now).div_ceil(1000)
+            );
+            let e = HttpError::new(StatusCode::TOO_MANY_REQUESTS, msg);
+            return Err((Some(Rejection::Cooldown), e));
+        }
+    }
+    wrong.count += 1;
+    wrong.last = now;
+    Ok(Reservation {
+        username: username.to_string(),
+        puzzle_id: puzzle_id.clone(),
+        is_settled: false,
+    })
+}
+
+/// an attempt counted by [`reserve_attempt`], taken back if it's dropped before [`settle_attempt`],
+/// eg: the client disconnected while it was being verified
+#[must_use]
+pub(super) struct Reservation {
+    username: String,
+    puzzle_id: PuzzleId,
+    is_settled: bool,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if self.is_settled {
+            return;
+        }
+        let username = std::mem::take(&mut self.username);
+        let puzzle_id = std::mem::take(&mut self.puzzle_id);
+        // NOTE: can't wait for the lock here
+        tokio::spawn(async move {
+            if let Some(team) = TEAMS.write().await.get_mut(&username) {
+                unreserve_attempt(team, &puzzle_id);
+            }
+        });
+    }
+}
+
+/// take back an attempt counted by [`reserve_attempt`]
+fn unreserve_attempt(team: &mut TeamState, puzzle_id: &PuzzleId) {
+    let Some(wrong) = team.wrong_attempts.get_mut(puzzle_id) else {
+        return;
+    };
+    wrong.count = wrong.count.saturating_sub(1);
+    if wrong.count == 0 && wrong.penalty == 0 {
+        _ = team.wrong_attempts.remove(puzzle_id);
+    }
+}
+
+/// record how an attempt counted by [`reserve_attempt`] went:
+/// - `Some(true)`: the team solved `puzzle`, returns the bonus it got for it
+/// - `Some(false)`: it was wrong indeed, take the penalty
+/// - `None`: couldn't be verified, don't count it
+pub(super) async fn settle_attempt(
+    mut reservation: Reservation,
+    puzzle: &Puzzle,
+    correct: Option<bool>,
+    time: Timestamp,
+) -> Result<PuzzleValue, HttpError> {
+    // decided while holding the lock, so two teams can't both be the first to solve it
+    let mut teams = TEAMS.write().await;
+    // nothing's awaited from here on, so it can't be dropped halfway
+    reservation.is_settled = true;
+    let (username, puzzle_id) = (reservation.username.as_str(), &reservation.puzzle_id);
+    let solves = (teams.values())
+        .filter(|team| team.solved.contains_key(puzzle_id))
+        .count();
+    let team = teams
+        .get_mut(username)
+        .or_internal_server_error("nincs ehhez a csapatnévhez előrehaladás rendelve")?;
+    let Some(wrong) = team.wrong_attempts.get_mut(puzzle_id) else {
+        return HttpError::internal_server_error("elveszett a próbálkozásotok");
+    };
+
+    if correct == Some(false) {
+        let penalty = puzzle.attempt_rules.unwrap_or(*ATTEMPT_RULES).penalty;
+        wrong.penalty = wrong.penalty.saturating_add(penalty);
+        return Ok(0);
+    }
+    unreserve_attempt(team, puzzle_id);
+    if correct.is_none() {
+        return Ok(0);
+    }
+    // another member of the team might've solved it while we were verifying
+    let Entry::Vacant(entry) = team.solved.entry(puzzle_id.clone()) else {
+        return Ok(0);
+    };
+    let bonus = puzzle.bonus(solves);
+    _ = entry.insert(Solve { time, bonus });
+    Ok(bonus)
+}
+
+/// get a clone of state: `TEAMS` and `PUZZLES`, with what the puzzles are currently worth
+///
+/// NOTE: as the worth of decaying puzzles depends on how many teams solved them,
+/// scores are recomputed for everyone each time
+pub(super) async fn get_game_state() -> (TeamsState, PuzzlesExisting) {
+    let teams = TEAMS.read().await.clone();
+    let solves = |id: &PuzzleId| {
+        teams
+            .values()
+            .filter(|team| team.solved.contains_key(id))
+            .count()
+    };
+    let existing_puzzles = (PUZZLES.read().await.iter())
+        .map(|(id, pzl)| (id.clone(), pzl.worth(solves(id))))
         .collect();
-    (TEAMS.read().await.clone(), existing_puzzles)
+    (teams, existing_puzzles)
+}
+
+/// every existing puzzle with its current worth, as contestants should see them, see [`PuzzleList`]
+pub(super) async fn get_puzzle_list() -> PuzzleList {
+    let (_, existing_puzzles) = get_game_state().await;
+    let mut puzzle_list = (PUZZLES.read().await.iter())
+        .map(|(id, puzzle)| PuzzleListing {
+            id: id.clone(),
+            // NOTE: might've been deleted since, 0 is close enough then
+            value: existing_puzzles.get(id).copied().unwrap_or_default(),
+            meta: puzzle.meta.clone(),
+        })
+        .collect::<PuzzleList>();
+    puzzle_list.sort_by(|a, b| (a.meta.order, &a.id).cmp(&(b.meta.order, &b.id)));
+    puzzle_list
+}
+
+/// current server time
+pub(super) fn now() -> Timestamp {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| u64::try_from(since_epoch.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or_default()
+}
+
+/// id of a session that's safe to show, as it can't be turned back into its `sid`
+pub(super) fn public_session_id(sid: &Uuid) -> String {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, sid.as_bytes()).to_string()
 }
 
 /// extract session id cookie from cookie headers
@@ -66,20 +708,165 @@ pub(super) async fn extract_sid_cookie(cookies: TypedHeader<Cookie>) -> Result<U
     Uuid::try_from(uuid).or_bad_request("érvénytelen munkamenet-azonosító süti")
 }
 
+/// extract session id cookie and return it with the team's name, if the session's still valid
+///
+/// refreshes the session's idle timeout, drops it if expired
+///
+/// NOTE: unknown sessions are treated as expired, as they might've just been pruned, see [`prune_sessions`]
+pub(super) async fn authenticate(
+    cookies: TypedHeader<Cookie>,
+) -> Result<(Uuid, String), HttpError> {
+    const EXPIRED: &str = "lejárt a munkamenet, lépj be újra";
+
+    let uuid = extract_sid_cookie(cookies).await?;
+    let now = now();
+    let mut user_ids = USER_IDS.write().await;
+    let session = user_ids.get_mut(&uuid).or_unauthorized(EXPIRED)?;
+    if session.is_expired(now) {
+        _ = user_ids.remove(&uuid);
+        return HttpError::unauthorized(EXPIRED);
+    }
+    session.last_seen = now;
+    Ok((uuid, session.username.clone()))
+}
+
+/// extract admin session id cookie and check whether the session's still valid, see `admin_login`
+pub(super) async fn authenticate_admin(cookies: &TypedHeader<Cookie>) -> Result<Uuid, HttpError> {
+    check_admin_pwd()?;
+    let uuid = cookies
+        .get("admin_sid")
+        .or_unauthorized("nem vagy bejelentkezve adminként")?;
+    let uuid =
+        Uuid::try_from(uuid).or_bad_request("érvénytelen admin munkamenet-azonosító süti")?;
+    let mut admin_sessions = ADMIN_SESSIONS.write().await;
+    let expires_at = *admin_sessions
+        .get(&uuid)
+        .or_unauthorized("nincs ilyen admin munkamenet, jelentkezz be újra")?;
+    if expires_at <= now() {
+        _ = admin_sessions.remove(&uuid);
+        return HttpError::unauthorized("lejárt az admin munkamenet, jelentkezz be újra");
+    }
+    Ok(uuid)
+}
+
+/// drop expired sessions every minute, forever
+pub async fn prune_sessions() {
+    loop {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        let now = now();
+        let mut user_ids = USER_IDS.write().await;
+        let session_count = user_ids.len();
+        user_ids.retain(|_, session| !session.is_expired(now));
+        let pruned_count = session_count - user_ids.len();
+        drop(user_ids);
+        ADMIN_SESSIONS
+            .write()
+            .await
+            .retain(|_, expires_at| *expires_at > now);
+
+        if pruned_count > 0 {
+            info!("{pruned_count} lejárt munkamenetet töröltünk");
+            #[cfg(feature = "server_state_save")]
+            state_save::save_state().await;
+        }
+    }
+}
+
 #[cfg(feature = "server_state_save")]
 pub(super) mod state_save {
-    use super::{PUZZLES, SALT, TEAMS, Teams, USER_IDS, check_admin_pwd};
+    use super::{
+        PUZZLES, SALT, SUBMISSIONS, Session, TEAM_SECRETS, TEAMS, TeamSecrets, USER_IDS,
+        check_admin_pwd, now, serde,
+    };
     use crate::backend::models::*;
     use chacha20poly1305::aead::{Aead, Nonce, OsRng};
     use chacha20poly1305::{AeadCore, KeyInit, XChaCha20Poly1305};
     use dioxus::prelude::*;
+    use std::collections::{HashMap, HashSet};
     use std::{env, path::Path, sync::LazyLock};
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use uuid::Uuid;
     use zeroize::Zeroize;
 
     type Res<T> = Result<T, Box<dyn std::error::Error>>;
+
     /// state that's stored on disk
-    type StateOnDisk = (TeamsState, PuzzleSolutions, Teams);
+    ///
+    /// NOTE: new fields should be `#[serde(default)]`, so older saves keep loading
+    #[derive(Default, serde::Deserialize, serde::Serialize)]
+    #[serde(crate = "dioxus::fullstack::serde")]
+    struct StateOnDisk {
+        teams: SavedTeamsState,
+        puzzles: PuzzleSolutions,
+        user_ids: SavedSessions,
+        #[serde(default)]
+        team_secrets: TeamSecrets,
+        #[serde(default)]
+        submissions: SubmissionLog,
+    }
+    /// state that was stored on disk before [`StateOnDisk`], still accepted when loading
+    type LegacyStateOnDisk = (SavedTeamsState, PuzzleSolutions, SavedSessions);
+
+    /// a team's progress used to be saved as the ids of the puzzles it solved only
+    #[derive(serde::Deserialize, serde::Serialize)]
+    #[serde(crate = "dioxus::fullstack::serde", untagged)]
+    enum SavedTeamState {
+        TeamState(TeamState),
+        Legacy(HashSet<PuzzleId>),
+    }
+    type SavedTeamsState = HashMap<String, SavedTeamState>;
+
+    impl From<SavedTeamState> for TeamState {
+        fn from(saved: SavedTeamState) -> Self {
+            match saved {
+                SavedTeamState::TeamState(team_state) => team_state,
+                // we can't know when they were solved, so consider them solved at once, right now
+                SavedTeamState::Legacy(puzzle_ids) => {
+                    let time = now();
+                    TeamState {
+                        solved: (puzzle_ids.into_iter())
+                            .map(|id| (id, Solve { time, bonus: 0 }))
+                            .collect(),
+                        ..Default::default()
+                    }
+                }
+            }
+        }
+    }
+
+    /// sessions used to be saved as the bare team name, without timestamps
+    #[derive(serde::Deserialize, serde::Serialize)]
+    #[serde(crate = "dioxus::fullstack::serde", untagged)]
+    enum SavedSession {
+        Session(Session),
+        Legacy(String),
+    }
+    type SavedSessions = HashMap<Uuid, SavedSession>;
+
+    impl From<SavedSession> for Session {
+        fn from(saved: SavedSession) -> Self {
+            match saved {
+                SavedSession::Session(session) => session,
+                // we can't know how old it is, so consider it expired, it's pruned right away
+                SavedSession::Legacy(username) => Session {
+                    username,
+                    created_at: 0,
+                    last_seen: 0,
+                },
+            }
+        }
+    }
+
+    impl From<LegacyStateOnDisk> for StateOnDisk {
+        fn from((teams, puzzles, user_ids): LegacyStateOnDisk) -> Self {
+            Self {
+                teams,
+                puzzles,
+                user_ids,
+                ..Default::default()
+            }
+        }
+    }
 
     static STATE_PATH: LazyLock<String> = LazyLock::new(|| {
         let def = String::from("apollo-state.cbor.encrypted"); // WARN: might not exist...
@@ -153,10 +940,17 @@ pub(super) mod state_save {
     async fn _save_state() -> Result<(), HttpError> {
         // internal server error
         let ise = |msg: String| HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, msg);
-        let teams_state = TEAMS.read().await.clone();
-        let puzzles_state = PUZZLES.read().await.clone();
-        let userid_state = USER_IDS.read().await.clone();
-        let disk_state: StateOnDisk = (teams_state, puzzles_state, userid_state);
+        let disk_state = StateOnDisk {
+            teams: (TEAMS.read().await.clone().into_iter())
+                .map(|(team, team_state)| (team, SavedTeamState::TeamState(team_state)))
+                .collect(),
+            puzzles: PUZZLES.read().await.clone(),
+            user_ids: (USER_IDS.read().await.clone().into_iter())
+                .map(|(uuid, session)| (uuid, SavedSession::Session(session)))
+                .collect(),
+            team_secrets: TEAM_SECRETS.read().await.clone(),
+            submissions: SUBMISSIONS.read().await.clone(),
+        };
 
         let mut state_buf = vec![];
         ciborium::into_writer(&disk_state, &mut state_buf)
@@ -175,20 +969,151 @@ pub(super) mod state_save {
         Ok(())
     }
 
+    /// decrypted `cbor` state, in any of the formats it was ever saved in
+    fn decode_state(decrypted: &[u8]) -> Res<StateOnDisk> {
+        match ciborium::from_reader(decrypted) {
+            Ok(disk_state) => Ok(disk_state),
+            Err(err) => Ok(ciborium::from_reader::<LegacyStateOnDisk, _>(decrypted)
+                .map_err(|_| err)?
+                .into()),
+        }
+    }
+
     /// load state from `STATE_PATH` into memory if it exists
     pub async fn load_state(raw_pwd: &[u8]) -> Res<()> {
         if !tokio::fs::try_exists(&*STATE_PATH).await? {
             warn!("nem létezik a megadott állapot-fájl({STATE_PATH:?})");
             return Ok(()); // no need to load, it's fine
         }
-        let (teams_state, puzzles_state, userid_state): StateOnDisk = {
-            let encrypted_data = decrypt_state(&*STATE_PATH, raw_pwd).await?;
-            ciborium::from_reader(encrypted_data.as_slice())?
-        };
-        PUZZLES.write().await.extend(puzzles_state);
-        TEAMS.write().await.extend(teams_state);
-        USER_IDS.write().await.extend(userid_state);
+        let disk_state = decode_state(&decrypt_state(&*STATE_PATH, raw_pwd).await?)?;
+        PUZZLES.write().await.extend(disk_state.puzzles);
+        TEAMS.write().await.extend(
+            (disk_state.teams.into_iter()).map(|(team, saved)| (team, TeamState::from(saved))),
+        );
+        USER_IDS.write().await.extend(
+            (disk_state.user_ids.into_iter()).map(|(uuid, saved)| (uuid, Session::from(saved))),
+        );
+        TEAM_SECRETS.write().await.extend(disk_state.team_secrets);
+        SUBMISSIONS.write().await.extend(disk_state.submissions);
         info!("sikeresen betöltöttük az elmentett állapotot a fájlból({STATE_PATH:?}) a memóriába");
         Ok(())
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// as puzzles were saved at first
+        #[derive(serde::Serialize)]
+        #[serde(crate = "dioxus::fullstack::serde")]
+        struct LegacyPuzzle {
+            solution: PuzzleSolutionHash,
+            value: PuzzleValue,
+        }
+
+        fn cbor(value: &impl serde::Serialize) -> Vec<u8> {
+            let mut buf = vec![];
+            ciborium::into_writer(value, &mut buf).expect("should encode");
+            buf
+        }
+
+        fn legacy_puzzles() -> HashMap<PuzzleId, LegacyPuzzle> {
+            let puzzle = LegacyPuzzle {
+                solution: String::from("$argon2id$v=19$m=16,t=2,p=1$c2FsdHNhbHQ$aGFzaA"),
+                value: 32,
+            };
+            HashMap::from([(PuzzleId::from("a"), puzzle)])
+        }
+
+        fn check_legacy_puzzles(puzzles: &PuzzleSolutions) {
+            let puzzle = &puzzles["a"];
+            assert_eq!(puzzle.value, 32);
+            assert!(puzzle.solution.starts_with("$argon2id$"));
+            assert!(puzzle.alternatives.is_empty());
+            assert!(puzzle.checker == AnswerChecker::Hashed);
+            assert!(puzzle.normalization == Normalization::default());
+            assert!(puzzle.decay.is_none() && puzzle.attempt_rules.is_none());
+        }
+
+        #[test]
+        fn decodes_legacy_tuple() {
+            let uuid = Uuid::new_v4();
+            let legacy = (
+                HashMap::from([("old", HashSet::from(["a"]))]),
+                legacy_puzzles(),
+                HashMap::from([(uuid, "old")]),
+            );
+            let disk_state = decode_state(&cbor(&legacy)).expect("should decode");
+
+            check_legacy_puzzles(&disk_state.puzzles);
+            let team = TeamState::from(disk_state.teams.into_iter().next().expect("a team").1);
+            assert_eq!(team.solved["a"].bonus, 0);
+            assert!(team.wrong_attempts.is_empty());
+            let session =
+                Session::from(disk_state.user_ids.into_iter().next().expect("a session").1);
+            assert!(session.username == "old" && session.is_expired(now()));
+            assert!(disk_state.team_secrets.is_empty() && disk_state.submissions.is_empty());
+        }
+
+        #[test]
+        fn round_trips() {
+            let team = TeamState {
+                solved: HashMap::from([("a".into(), Solve { time: 1, bonus: 2 })]),
+                wrong_attempts: HashMap::from([(
+                    "a".into(),
+                    WrongAttempts {
+                        count: 3,
+                        last: 4,
+                        penalty: 5,
+                    },
+                )]),
+            };
+            let disk_state = StateOnDisk {
+                teams: HashMap::from([("t".into(), SavedTeamState::TeamState(team.clone()))]),
+                user_ids: HashMap::from([(
+                    Uuid::new_v4(),
+                    SavedSession::Session(Session::new("t".into())),
+                )]),
+                team_secrets: HashMap::from([("t".into(), "hash".into())]),
+                ..Default::default()
+            };
+            let decoded = decode_state(&cbor(&disk_state)).expect("should decode");
+
+            let (name, saved) = decoded.teams.into_iter().next().expect("a team");
+            assert_eq!(name, "t");
+            assert!(TeamState::from(saved) == team);
+            assert_eq!(decoded.team_secrets["t"], "hash");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: AttemptRules = AttemptRules {
+        penalty: 0,
+        cooldown_after: 0,
+        cooldown_secs: 0,
+        max_attempts: 1,
+    };
+
+    #[tokio::test]
+    async fn dropped_reservation_is_taken_back() {
+        let (team, puzzle_id) = ("dropped", PuzzleId::from("p"));
+        _ = TEAMS
+            .write()
+            .await
+            .insert(team.into(), TeamState::default());
+
+        let reservation = reserve_attempt(team, &puzzle_id, &RULES).await;
+        assert!(reservation.is_ok());
+        // the only attempt is reserved
+        assert!(reserve_attempt(team, &puzzle_id, &RULES).await.is_err());
+        drop(reservation);
+        tokio::task::yield_now().await;
+
+        assert!(TEAMS.read().await[team].wrong_attempts.is_empty());
+        assert!(reserve_attempt(team, &puzzle_id, &RULES).await.is_ok());
+    }
 }
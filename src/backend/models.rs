@@ -1,15 +1,254 @@
 use dioxus::fullstack::serde;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::net::IpAddr;
 
 // SECURITY: SecretString, with manual impls?
-#[derive(Clone, PartialOrd, Ord, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(crate = "dioxus::fullstack::serde")]
 pub struct Puzzle {
-    /// argon2-encoded solution hash (not the raw solution)
+    /// argon2-encoded solution hash (not the raw solution), empty unless `checker` is [`AnswerChecker::Hashed`]
+    #[serde(default)]
     pub solution: PuzzleSolutionHash,
-    /// how much it's worth
+    /// other accepted solutions, hashed with the same salt as `solution`,
+    /// so that answers need to be hashed only once
+    #[serde(default)]
+    pub alternatives: Vec<PuzzleSolutionHash>,
+    /// how answers are checked
+    ///
+    /// SECURITY: holds the solution in the clear unless it's [`AnswerChecker::Hashed`],
+    /// only ever in the encrypted state file, never send it to clients
+    #[serde(default)]
+    pub checker: AnswerChecker,
+    /// applied to solutions before hashing them and to answers before verifying them
+    ///
+    /// NOTE: can't be changed later, as the hashes wouldn't match anymore
+    #[serde(default)]
+    pub normalization: Normalization,
+    /// how much it's worth, initially if it `decay`s
+    pub value: PuzzleValue,
+    /// makes it worth less and less as more teams solve it
+    #[serde(default)]
+    pub decay: Option<Decay>,
+    /// extra points for the first, second, ... team to solve it, on top of its `value`
+    #[serde(default)]
+    pub bonuses: Vec<PuzzleValue>,
+    /// what wrong answers cost, the server-wide rules if `None`
+    #[serde(default)]
+    pub attempt_rules: Option<AttemptRules>,
+    /// what contestants see of it, see [`PuzzleListing`]
+    #[serde(default)]
+    pub meta: PuzzleMeta,
+}
+
+#[cfg(feature = "server")]
+impl Puzzle {
+    /// how much it's worth for every team that solved it, when `solves` teams have
+    pub fn worth(&self, solves: usize) -> PuzzleValue {
+        match &self.decay {
+            None => self.value,
+            Some(decay) => decay.apply(self.value, solves),
+        }
+    }
+
+    /// extra points for the team solving it after `solves` others did
+    pub fn bonus(&self, solves: usize) -> PuzzleValue {
+        self.bonuses.get(solves).copied().unwrap_or(0)
+    }
+}
+
+/// what contestants see of a puzzle besides its id and value, all optional
+#[derive(Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde", default)]
+pub struct PuzzleMeta {
+    pub title: String,
+    /// markdown
+    pub statement: String,
+    /// categories or tags, eg: "web", "kripto"
+    pub tags: Vec<String>,
+    pub difficulty: Option<Difficulty>,
+    /// puzzles are listed by this, ascending, then by id
+    pub order: i32,
+}
+
+#[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// a puzzle as contestants see it, nothing about its solution
+#[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct PuzzleListing {
+    pub id: PuzzleId,
+    /// what it's worth now, see [`Decay`]
     pub value: PuzzleValue,
+    pub meta: PuzzleMeta,
+}
+/// all the existing puzzles in the order they should be shown, see [`PuzzleMeta::order`]
+pub type PuzzleList = Vec<PuzzleListing>;
+
+/// how answers to a puzzle are checked, after being normalized, see [`Normalization`]
+#[derive(Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub enum AnswerChecker {
+    /// matches one of the hashed `solution` or `alternatives` exactly
+    #[default]
+    Hashed,
+    /// a number between `min` and `max`, inclusive, eg: 3.1416 ± 0.001
+    ///
+    /// NOTE: a decimal comma is accepted too, eg: "3,1416"
+    NumericRange { min: f64, max: f64 },
+    /// matches the whole of `pattern`, a [regex](https://docs.rs/regex/latest/regex/#syntax)
+    Regex { pattern: String },
+    /// `program` on the server, an absolute path, accepts it on its stdin by exiting with 0
+    ///
+    /// NOTE: for answers that can't be checked against a fixed string, eg: program output
+    External { program: String },
+    /// a different answer for every team, so they can't just pass it on to each other:
+    /// `template` with [`HMAC_PLACEHOLDER`] replaced by an HMAC of the team's name keyed with `secret`
+    ///
+    /// NOTE: `secret` is generated by the server if it's empty, or kept when editing, see [`TeamAnswers`]
+    PerTeam { secret: String, template: String },
 }
+/// replaced by the team's own HMAC in the `template` of [`AnswerChecker::PerTeam`], eg: `flag{{hmac}}`
+pub const HMAC_PLACEHOLDER: &str = "{hmac}";
+/// the answer each team is expected to submit to the puzzles with [`AnswerChecker::PerTeam`], by team name
+pub type TeamAnswers = HashMap<PuzzleId, HashMap<String, PuzzleSolution>>;
+
+/// how answers are tidied up, so that eg: "  Answer " and "answer" can both be accepted
+#[derive(
+    Clone, Copy, Default, PartialOrd, Ord, PartialEq, Eq, serde::Deserialize, serde::Serialize,
+)]
+#[serde(crate = "dioxus::fullstack::serde", default)]
+pub struct Normalization {
+    /// remove leading and trailing whitespace
+    pub trim: bool,
+    /// compare case-insensitively
+    pub case_fold: bool,
+    /// unicode NFC, so that eg: "é" is the same whether typed as one character or two
+    pub nfc: bool,
+    /// turn runs of whitespace into a single space
+    pub collapse_whitespace: bool,
+    /// "árvíztűrő" is the same as "arvizturo"
+    pub strip_accents: bool,
+}
+
+#[cfg(feature = "server")]
+impl Normalization {
+    /// `raw` tidied up as configured
+    ///
+    /// SECURITY: the result is a copy of `raw`, zeroize it too
+    pub fn apply(&self, raw: &str) -> String {
+        use unicode_normalization::UnicodeNormalization;
+
+        // NOTE: composed first, so accents can be stripped one character at a time
+        let mut normalized = if self.nfc || self.strip_accents {
+            raw.nfc().collect()
+        } else {
+            raw.to_owned()
+        };
+        if self.strip_accents {
+            normalized = normalized.chars().map(strip_hungarian_accent).collect();
+        }
+        if self.case_fold {
+            normalized = normalized.to_lowercase();
+        }
+        if self.collapse_whitespace {
+            let mut collapsed = String::with_capacity(normalized.len());
+            for c in normalized.chars() {
+                if !c.is_whitespace() {
+                    collapsed.push(c);
+                } else if !collapsed.ends_with(' ') {
+                    collapsed.push(' ');
+                }
+            }
+            normalized = collapsed;
+        }
+        if self.trim {
+            normalized = normalized.trim().to_owned();
+        }
+        normalized
+    }
+}
+
+/// the letter `c` without its accent, if it's an accented letter of the hungarian alphabet
+#[cfg(feature = "server")]
+fn strip_hungarian_accent(c: char) -> char {
+    match c {
+        'á' => 'a',
+        'é' => 'e',
+        'í' => 'i',
+        'ó' | 'ö' | 'ő' => 'o',
+        'ú' | 'ü' | 'ű' => 'u',
+        'Á' => 'A',
+        'É' => 'E',
+        'Í' => 'I',
+        'Ó' | 'Ö' | 'Ő' => 'O',
+        'Ú' | 'Ü' | 'Ű' => 'U',
+        _ => c,
+    }
+}
+
+/// dynamic scoring, CTFd-style
+#[derive(Clone, PartialOrd, Ord, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct Decay {
+    /// it's never worth less than this
+    pub minimum: PuzzleValue,
+    /// after how many solves (besides the first) it's only worth `minimum`
+    pub solves: u32,
+    pub curve: DecayCurve,
+}
+
+/// how the worth of a puzzle goes from its initial value to the minimum
+#[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub enum DecayCurve {
+    /// loses the same amount with each solve
+    Linear,
+    /// loses little with the first few solves, more later on, like CTFd's default
+    Quadratic,
+}
+
+#[cfg(feature = "server")]
+impl Decay {
+    /// worth of a puzzle with `initial` value, solved by `solves` teams
+    ///
+    /// the first solve doesn't count, so the first team to solve it sees the `initial` value
+    fn apply(&self, initial: PuzzleValue, solves: usize) -> PuzzleValue {
+        // NOTE: u128, so range * n * n can't overflow, it's less than 2^96
+        let n = u128::try_from(solves.saturating_sub(1)).unwrap_or(u128::MAX);
+        let d = u128::from(self.solves);
+        if n >= d || self.minimum >= initial {
+            return self.minimum.min(initial);
+        }
+        let range = u128::from(initial - self.minimum);
+        let loss = match self.curve {
+            DecayCurve::Linear => range * n / d,
+            DecayCurve::Quadratic => range * n * n / (d * d),
+        };
+        // NOTE: loss < range, as n < d
+        initial - PuzzleValue::try_from(loss).unwrap_or(initial - self.minimum)
+    }
+}
+
+/// what happens to the teams' progress on a puzzle when it's deleted
+#[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub enum DeletePolicy {
+    /// forget who solved it or tried to
+    Purge,
+    /// keep solves and wrong attempts as history, they just don't count towards scores anymore
+    ///
+    /// NOTE: so its id can't be used for a new puzzle
+    KeepHistory,
+}
+
+/// milliseconds since the unix epoch, server time
+pub type Timestamp = u64;
 
 pub type PuzzleId = String;
 /// how much points you get for solving a puzzle
@@ -20,7 +259,453 @@ pub type PuzzleSolutionHash = String;
 pub type PuzzlesExisting = HashMap<PuzzleId, PuzzleValue>;
 /// all the puzzles with their values and solutions
 pub type PuzzleSolutions = HashMap<PuzzleId, Puzzle>;
+/// what happens when a team sends wrong answers for a puzzle
+#[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct AttemptRules {
+    /// points taken for each wrong attempt
+    pub penalty: PuzzleValue,
+    /// the team has to wait `cooldown_secs` after this many wrong attempts, never if 0
+    pub cooldown_after: u32,
+    pub cooldown_secs: u64,
+    /// the puzzle locks for the team after this many wrong attempts, never if 0
+    pub max_attempts: u32,
+}
+
+/// a team solving a puzzle
+#[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct Solve {
+    pub time: Timestamp,
+    /// extra points for being one of the first to solve it, see [`Puzzle::bonuses`]
+    pub bonus: PuzzleValue,
+}
 /// solved puzzles of a team
-pub type SolvedPuzzles = HashSet<PuzzleId>;
+pub type SolvedPuzzles = HashMap<PuzzleId, Solve>;
+
+/// a team's wrong answers for a puzzle, see [`AttemptRules`]
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct WrongAttempts {
+    pub count: u32,
+    pub last: Timestamp,
+    /// points taken for them in total
+    pub penalty: PuzzleValue,
+}
+
+/// progress of a team
+#[derive(Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct TeamState {
+    pub solved: SolvedPuzzles,
+    /// counted for the whole team, not per session
+    #[serde(default)]
+    pub wrong_attempts: HashMap<PuzzleId, WrongAttempts>,
+}
 /// progress of each team, which puzzles they've solved
-pub type TeamsState = HashMap<String, SolvedPuzzles>;
+pub type TeamsState = HashMap<String, TeamState>;
+
+/// a team's place in the competition
+#[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct Standing {
+    /// teams with equal scores, reached at the same time, share their rank
+    pub rank: usize,
+    pub team: String,
+    /// `score` and `bonus`, less `penalty`, what the teams are ranked by
+    ///
+    /// NOTE: it's 0 rather than negative if penalties outweigh the rest
+    pub total: PuzzleValue,
+    /// sum of the values of the solved puzzles
+    pub score: PuzzleValue,
+    /// sum of the bonuses for solving puzzles early
+    pub bonus: PuzzleValue,
+    /// sum of the points taken for wrong attempts
+    pub penalty: PuzzleValue,
+    /// how many of the existing puzzles the team has solved
+    pub solve_count: usize,
+    /// when the team reached its current score
+    pub last_solve: Option<Timestamp>,
+}
+/// every team, best first
+pub type Standings = Vec<Standing>;
+
+/// sum up the value of each team's solved puzzles and bonuses, take their penalties,
+/// order them by total score, then by who reached it first, then name
+///
+/// NOTE: solves of puzzles that don't exist (anymore) don't count
+pub fn rank_teams(teams: &TeamsState, puzzles: &PuzzlesExisting) -> Standings {
+    let mut standings = teams
+        .iter()
+        .map(|(team, state)| {
+            let counted = (state.solved.iter()).filter(|(id, _)| puzzles.contains_key(*id));
+            // NOTE: saturating, so huge values can't overflow
+            let score =
+                (counted.clone().map(|(id, _)| puzzles[id])).fold(0, PuzzleValue::saturating_add);
+            let bonus = (counted.clone().map(|(_, solve)| solve.bonus))
+                .fold(0, PuzzleValue::saturating_add);
+            let penalty = (state.wrong_attempts.iter())
+                .filter(|(id, _)| puzzles.contains_key(*id))
+                .map(|(_, wrong)| wrong.penalty)
+                .fold(0, PuzzleValue::saturating_add);
+            Standing {
+                rank: 0,
+                team: team.clone(),
+                total: score.saturating_add(bonus).saturating_sub(penalty),
+                score,
+                bonus,
+                penalty,
+                solve_count: counted.clone().count(),
+                last_solve: counted.map(|(_, solve)| solve.time).max(),
+            }
+        })
+        .collect::<Vec<_>>();
+    standings.sort_by(|a, b| {
+        (b.total.cmp(&a.total))
+            .then_with(|| a.last_solve.cmp(&b.last_solve))
+            .then_with(|| a.team.cmp(&b.team))
+    });
+
+    let mut prev: Option<(PuzzleValue, Option<Timestamp>, usize)> = None;
+    for (i, standing) in standings.iter_mut().enumerate() {
+        standing.rank = match prev {
+            Some((total, last_solve, rank))
+                if total == standing.total && last_solve == standing.last_solve =>
+            {
+                rank
+            }
+            _ => i + 1,
+        };
+        prev = Some((standing.total, standing.last_solve, standing.rank));
+    }
+    standings
+}
+
+/// one of the sessions of a team, without anything that'd let you take it over
+#[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct SessionInfo {
+    /// public id of the session, not its `sid`
+    pub id: String,
+    /// whether it's the session that asked
+    pub current: bool,
+    /// when it joined
+    pub created_at: Timestamp,
+    /// when it was last used
+    pub last_seen: Timestamp,
+}
+
+/// an attempt at solving a puzzle, whether it was right or not
+#[derive(Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub struct Submission {
+    pub team: String,
+    /// public id of the session it was sent from
+    pub session: String,
+    pub puzzle_id: PuzzleId,
+    pub time: Timestamp,
+    pub correct: bool,
+    /// address the server got it from
+    pub ip: IpAddr,
+    /// the team whose own answer it was, if it was another team's, see [`AnswerChecker::PerTeam`]
+    #[serde(default)]
+    pub shared_from: Option<String>,
+    /// why the answer wasn't even checked, it's not `correct` then
+    #[serde(default)]
+    pub rejected: Option<Rejection>,
+}
+/// why a submission wasn't checked
+#[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+pub enum Rejection {
+    UnknownPuzzle,
+    AlreadySolved,
+    /// out of attempts, see [`AttemptRules::max_attempts`]
+    Locked,
+    /// see [`AttemptRules::cooldown_after`]
+    Cooldown,
+    /// the server couldn't check it, eg: an external checker failed
+    CheckFailed,
+}
+/// all the submissions, oldest first, append-only
+pub type SubmissionLog = Vec<Submission>;
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "server")]
+    mod decay {
+        use super::super::*;
+
+        const LINEAR: Decay = Decay {
+            minimum: 100,
+            solves: 10,
+            curve: DecayCurve::Linear,
+        };
+        const QUADRATIC: Decay = Decay {
+            curve: DecayCurve::Quadratic,
+            ..LINEAR
+        };
+
+        #[test]
+        fn skips_first_solve() {
+            for decay in [LINEAR, QUADRATIC] {
+                assert_eq!(decay.apply(500, 0), 500);
+                assert_eq!(decay.apply(500, 1), 500);
+            }
+        }
+
+        #[test]
+        fn curves() {
+            // 400 to lose over 10 solves
+            assert_eq!(LINEAR.apply(500, 2), 460);
+            assert_eq!(LINEAR.apply(500, 6), 300);
+            assert_eq!(LINEAR.apply(500, 10), 140);
+            assert_eq!(QUADRATIC.apply(500, 2), 496);
+            assert_eq!(QUADRATIC.apply(500, 6), 400);
+            assert_eq!(QUADRATIC.apply(500, 10), 176);
+        }
+
+        #[test]
+        fn bottoms_out_at_minimum() {
+            for decay in [LINEAR, QUADRATIC] {
+                assert_eq!(decay.apply(500, 11), 100);
+                assert_eq!(decay.apply(500, 1000), 100);
+                assert_eq!(decay.apply(500, usize::MAX), 100);
+            }
+            let instant = Decay {
+                solves: 0,
+                ..LINEAR
+            };
+            assert_eq!(instant.apply(500, 1), 100);
+        }
+
+        #[test]
+        fn minimum_above_initial() {
+            for decay in [LINEAR, QUADRATIC] {
+                assert_eq!(decay.apply(100, 5), 100);
+                assert_eq!(decay.apply(50, 5), 50);
+                assert_eq!(decay.apply(50, 1000), 50);
+            }
+        }
+
+        #[test]
+        fn huge_values() {
+            for curve in [DecayCurve::Linear, DecayCurve::Quadratic] {
+                let decay = Decay {
+                    minimum: 0,
+                    solves: u32::MAX,
+                    curve,
+                };
+                let solves = usize::try_from(u32::MAX).unwrap_or(usize::MAX);
+                assert!(decay.apply(PuzzleValue::MAX, solves) > 0);
+                assert_eq!(decay.apply(PuzzleValue::MAX, 1), PuzzleValue::MAX);
+            }
+        }
+    }
+
+    mod ranking {
+        use super::super::*;
+
+        /// a team that solved each of `solves` at the time given, and lost `penalty` on `p`
+        fn team(solves: &[(&str, Timestamp)], penalty: PuzzleValue) -> TeamState {
+            let solved = (solves.iter())
+                .map(|(id, time)| {
+                    (
+                        id.to_string(),
+                        Solve {
+                            time: *time,
+                            bonus: 0,
+                        },
+                    )
+                })
+                .collect();
+            let wrong = WrongAttempts {
+                count: 1,
+                last: 0,
+                penalty,
+            };
+            TeamState {
+                solved,
+                wrong_attempts: HashMap::from([(PuzzleId::from("p"), wrong)]),
+            }
+        }
+
+        fn puzzles(values: &[(&str, PuzzleValue)]) -> PuzzlesExisting {
+            (values.iter())
+                .map(|(id, value)| (id.to_string(), *value))
+                .collect()
+        }
+
+        /// (rank, team, total) of each, best first
+        fn ranked(
+            teams: &TeamsState,
+            puzzles: &PuzzlesExisting,
+        ) -> Vec<(usize, String, PuzzleValue)> {
+            (rank_teams(teams, puzzles).into_iter())
+                .map(|standing| (standing.rank, standing.team, standing.total))
+                .collect()
+        }
+
+        #[test]
+        fn earlier_wins_ties() {
+            let puzzles = puzzles(&[("a", 10), ("b", 10), ("p", 1)]);
+            let teams = TeamsState::from([
+                ("late".into(), team(&[("a", 1), ("b", 5)], 0)),
+                ("early".into(), team(&[("b", 2), ("a", 3)], 0)),
+                ("best".into(), team(&[("a", 9), ("b", 9), ("p", 9)], 0)),
+            ]);
+            assert_eq!(
+                ranked(&teams, &puzzles),
+                [
+                    (1, "best".into(), 21),
+                    (2, "early".into(), 20),
+                    (3, "late".into(), 20),
+                ]
+            );
+        }
+
+        #[test]
+        fn shares_ranks() {
+            let puzzles = puzzles(&[("a", 10), ("b", 5)]);
+            let teams = TeamsState::from([
+                ("c".into(), team(&[("b", 1)], 0)),
+                ("b".into(), team(&[("a", 2)], 0)),
+                ("a".into(), team(&[("a", 2)], 0)),
+                ("d".into(), team(&[], 0)),
+                ("e".into(), team(&[], 0)),
+            ]);
+            assert_eq!(
+                ranked(&teams, &puzzles),
+                [
+                    (1, "a".into(), 10),
+                    (1, "b".into(), 10),
+                    (3, "c".into(), 5),
+                    (4, "d".into(), 0),
+                    (4, "e".into(), 0),
+                ]
+            );
+        }
+
+        #[test]
+        fn penalties_and_deleted_puzzles() {
+            let puzzles = puzzles(&[("a", 10), ("p", 1)]);
+            let teams = TeamsState::from([
+                ("penalized".into(), team(&[("a", 1)], 4)),
+                ("negative".into(), team(&[("a", 1)], 40)),
+                ("deleted".into(), team(&[("gone", 1)], 0)),
+            ]);
+            let standings = rank_teams(&teams, &puzzles);
+            assert_eq!(standings[0].team, "penalized");
+            assert_eq!((standings[0].score, standings[0].penalty), (10, 4));
+            assert_eq!(standings[0].total, 6);
+            assert_eq!(
+                (standings[1].team.as_str(), standings[1].total),
+                ("deleted", 0)
+            );
+            assert_eq!(
+                (standings[1].solve_count, standings[1].last_solve),
+                (0, None)
+            );
+            // not negative
+            assert_eq!(
+                (standings[2].team.as_str(), standings[2].total),
+                ("negative", 0)
+            );
+        }
+
+        #[test]
+        fn saturates() {
+            let puzzles = puzzles(&[("a", PuzzleValue::MAX), ("b", PuzzleValue::MAX)]);
+            let teams = TeamsState::from([("t".into(), team(&[("a", 1), ("b", 2)], 0))]);
+            let standings = rank_teams(&teams, &puzzles);
+            assert_eq!(standings[0].score, PuzzleValue::MAX);
+            assert_eq!(standings[0].total, PuzzleValue::MAX);
+        }
+    }
+
+    #[cfg(feature = "server")]
+    mod normalization {
+        use super::super::*;
+
+        const NONE: Normalization = Normalization {
+            trim: false,
+            case_fold: false,
+            nfc: false,
+            collapse_whitespace: false,
+            strip_accents: false,
+        };
+
+        #[test]
+        fn off_by_default() {
+            let raw = "  Árvíz\t\ttűrő e\u{301} ";
+            assert_eq!(Normalization::default().apply(raw), raw);
+        }
+
+        #[test]
+        fn whitespace() {
+            let raw = " \t a  b\n\nc ";
+            let trim = Normalization { trim: true, ..NONE };
+            let collapse = Normalization {
+                collapse_whitespace: true,
+                ..NONE
+            };
+            let both = Normalization {
+                trim: true,
+                collapse_whitespace: true,
+                ..NONE
+            };
+            assert_eq!(trim.apply(raw), "a  b\n\nc");
+            assert_eq!(collapse.apply(raw), " a b c ");
+            assert_eq!(both.apply(raw), "a b c");
+        }
+
+        #[test]
+        fn case_fold() {
+            let case_fold = Normalization {
+                case_fold: true,
+                ..NONE
+            };
+            assert_eq!(case_fold.apply("ÁrvíZ ŐSZ"), "árvíz ősz");
+        }
+
+        #[test]
+        fn nfc() {
+            let nfc = Normalization { nfc: true, ..NONE };
+            // e followed by a combining acute accent
+            assert_eq!(nfc.apply("e\u{301}"), "é");
+            assert_eq!(nfc.apply("é"), "é");
+        }
+
+        #[test]
+        fn hungarian_accents() {
+            let strip = Normalization {
+                strip_accents: true,
+                ..NONE
+            };
+            assert_eq!(
+                strip.apply("árvíztűrő tükörfúrógép"),
+                "arvizturo tukorfurogep"
+            );
+            assert_eq!(
+                strip.apply("ÁRVÍZTŰRŐ TÜKÖRFÚRÓGÉP"),
+                "ARVIZTURO TUKORFUROGEP"
+            );
+            // composed first, even without `nfc`
+            assert_eq!(strip.apply("o\u{30b}"), "o");
+            // only the hungarian ones
+            assert_eq!(strip.apply("ñ à"), "ñ à");
+        }
+
+        #[test]
+        fn everything() {
+            let all = Normalization {
+                trim: true,
+                case_fold: true,
+                nfc: true,
+                collapse_whitespace: true,
+                strip_accents: true,
+            };
+            assert_eq!(all.apply("  Árvíz\t\tTŰRŐ  E\u{301}  "), "arviz turo e");
+        }
+    }
+}
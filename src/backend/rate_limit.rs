@@ -0,0 +1,148 @@
+use super::logic::{client_ip, env_or};
+use dioxus::fullstack::headers::{Cookie, HeaderMapExt};
+use dioxus::fullstack::{Json, StatusCode, serde};
+use dioxus::prelude::*;
+use dioxus::server::axum::extract::{ConnectInfo, Request};
+use dioxus::server::axum::http::header::RETRY_AFTER;
+use dioxus::server::axum::middleware::Next;
+use dioxus::server::axum::response::{IntoResponse, Response};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// endpoints worth brute-forcing: `join`, `submit_solution`, `set_passwd`, `admin_login` and `set_solution`
+const LIMITED_PATHS: [&str; 5] = [
+    "/api/join",
+    "/api/submit",
+    "/api/set_admin_password",
+    "/api/admin/login",
+    "/api/set_solution",
+];
+
+/// how many requests a client may send to an endpoint at once, no limits if 0
+static BURST: LazyLock<u32> = LazyLock::new(|| env_or("APOLLO_RATE_LIMIT_BURST", 10));
+/// how many requests a client may send to an endpoint per minute in the long run, no limits if 0
+static PER_MIN: LazyLock<u32> = LazyLock::new(|| env_or("APOLLO_RATE_LIMIT_PER_MIN", 20));
+
+/// forget buckets that filled up once there are this many
+const MAX_BUCKETS: usize = 4096;
+
+/// who's sending the requests
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Client {
+    Ip(IpAddr),
+    /// by `sid` cookie, valid or not
+    Session(String),
+}
+
+/// tokens refill at `PER_MIN`, up to `BURST`, each request takes one
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(now: Instant) -> Self {
+        Self {
+            tokens: f64::from(*BURST),
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let per_sec = f64::from(*PER_MIN) / 60.;
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_sec).min(f64::from(*BURST));
+        self.updated = now;
+    }
+
+    /// how long until a token's there, `None` if there's one already
+    fn wait(&self) -> Option<Duration> {
+        let per_sec = f64::from(*PER_MIN) / 60.;
+        (self.tokens < 1.).then(|| Duration::from_secs_f64((1. - self.tokens) / per_sec))
+    }
+}
+
+type Buckets = HashMap<(&'static str, Client), Bucket>;
+static BUCKETS: LazyLock<Mutex<Buckets>> = LazyLock::new(|| Mutex::new(Buckets::new()));
+
+/// take a token from the bucket of each of the `clients` for `path`,
+/// or none of them if any is empty, then returns how long to wait
+async fn take_tokens(path: &'static str, clients: Vec<Client>) -> Result<(), Duration> {
+    let now = Instant::now();
+    let mut buckets = BUCKETS.lock().await;
+    if buckets.len() > MAX_BUCKETS {
+        buckets.retain(|_, bucket| {
+            bucket.refill(now);
+            bucket.tokens < f64::from(*BURST)
+        });
+    }
+
+    let mut wait = None;
+    for client in &clients {
+        let bucket = (buckets.entry((path, client.clone()))).or_insert_with(|| Bucket::full(now));
+        bucket.refill(now);
+        wait = wait.max(bucket.wait());
+    }
+    if let Some(wait) = wait {
+        return Err(wait);
+    }
+    for client in clients {
+        if let Some(bucket) = buckets.get_mut(&(path, client)) {
+            bucket.tokens -= 1.;
+        }
+    }
+    Ok(())
+}
+
+/// same as the error payload of server functions, so clients can show the message
+#[derive(serde::Serialize)]
+#[serde(crate = "dioxus::fullstack::serde")]
+struct ErrorPayload {
+    message: String,
+    code: u16,
+}
+
+/// token-bucket rate limiting per client address and per session,
+/// on the endpoints listed in [`LIMITED_PATHS`]
+///
+/// rejected requests get `429 Too Many Requests` with a `Retry-After` header
+pub async fn rate_limit(
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let Some(path) = LIMITED_PATHS.into_iter().find(|limited| *limited == path) else {
+        return next.run(request).await;
+    };
+    if *BURST == 0 || *PER_MIN == 0 {
+        return next.run(request).await;
+    }
+
+    let ip = client_ip(peer.ip(), request.headers());
+    let mut clients = vec![Client::Ip(ip)];
+    let sid =
+        (request.headers().typed_get::<Cookie>()).and_then(|c| c.get("sid").map(String::from));
+    if let Some(sid) = sid {
+        clients.push(Client::Session(sid));
+    }
+
+    let Err(wait) = take_tokens(path, clients).await else {
+        return next.run(request).await;
+    };
+    let retry_after = wait.as_secs_f64().ceil().max(1.);
+    warn!("túl sok kérés innen: {ip}, ide: {path}");
+    let payload = ErrorPayload {
+        message: format!("túl sok kérés, próbáld újra {retry_after} másodperc múlva"),
+        code: StatusCode::TOO_MANY_REQUESTS.as_u16(),
+    };
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(RETRY_AFTER, retry_after.to_string())],
+        Json(payload),
+    )
+        .into_response()
+}
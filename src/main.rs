@@ -7,17 +7,38 @@ fn main() {
     eprintln!("{}", env!("BANNER").replace(r"\n", "\n").trim_matches('"')); // had to be escaped, see build.rs
 
     #[cfg(feature = "server")]
-    dioxus::serve(|| async move {
-        use dioxus::cli_config::fullstack_address_or_localhost as dx_server_addr;
-        use dioxus::prelude::*;
-
-        info!("serving on http://{}", dx_server_addr());
-        info!("initial admin password: {:?}", *crate::backend::INIT_PWD);
-
-        let router = dioxus::server::router(app::App);
-        Ok(router)
-    });
+    tokio::runtime::Runtime::new()
+        .expect("couldn't start the async runtime")
+        .block_on(serve());
 
     #[cfg(not(feature = "server"))]
     dioxus::launch(app::App);
 }
+
+/// like `dioxus::serve`, but lets endpoints know the address of the client
+///
+/// NOTE: the server can't be hot-patched this way, only the client
+#[cfg(feature = "server")]
+async fn serve() {
+    use dioxus::cli_config::fullstack_address_or_localhost as dx_server_addr;
+    use dioxus::prelude::*;
+    use dioxus::server::axum;
+    use std::net::SocketAddr;
+
+    info!("serving on http://{}", dx_server_addr());
+    info!("initial admin password: {:?}", *crate::backend::INIT_PWD);
+
+    tokio::spawn(crate::backend::prune_sessions());
+
+    let listener = tokio::net::TcpListener::bind(dx_server_addr())
+        .await
+        .expect("couldn't bind to the server address");
+    let router = dioxus::server::router(app::App)
+        .layer(axum::middleware::from_fn(crate::backend::rate_limit));
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .expect("couldn't serve apollo");
+}